@@ -1,7 +1,21 @@
+use std::env;
+use std::process::Command;
+
 fn main() {
     glib_build_tools::compile_resources(
         &["resources"],
         "resources/resources.gresource.xml",
         "systemd-gtk.gresource",
     );
+
+    // bundled with the binary, which falls back to it if the schema is not installed
+    println!("cargo:rerun-if-changed=resources/com.journeycorner.systemd-gtk.gschema.xml");
+    let out_dir = env::var("OUT_DIR").expect("Cargo sets `OUT_DIR` for build scripts.");
+    let status = Command::new("glib-compile-schemas")
+        .arg("--strict")
+        .arg(format!("--targetdir={out_dir}"))
+        .arg("resources")
+        .status()
+        .expect("`glib-compile-schemas` needs to be installed.");
+    assert!(status.success(), "The settings schema needs to compile.");
 }
@@ -0,0 +1,144 @@
+use crate::json;
+use adw::glib;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// A privileged operation, as recorded in the audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub user: String,
+    pub unit: String,
+    pub action: String,
+    pub outcome: String,
+    pub error: String,
+}
+
+impl AuditEntry {
+    /// Creates an entry for an action the current user has just taken.
+    pub fn new(unit: &str, action: &str, outcome: &str, error: &str) -> Self {
+        let timestamp = glib::DateTime::now_local()
+            .and_then(|now| now.format_iso8601())
+            .map(|timestamp| timestamp.to_string())
+            .unwrap_or_default();
+        AuditEntry {
+            timestamp,
+            user: glib::user_name().to_string_lossy().to_string(),
+            unit: unit.to_string(),
+            action: action.to_string(),
+            outcome: outcome.to_string(),
+            error: error.to_string(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        json::object(&[
+            ("timestamp", &self.timestamp),
+            ("user", &self.user),
+            ("unit", &self.unit),
+            ("action", &self.action),
+            ("outcome", &self.outcome),
+            ("error", &self.error),
+        ])
+    }
+
+    fn from_json(line: &str) -> Option<Self> {
+        let fields = json::parse_object(line)?;
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.clone())
+                .unwrap_or_default()
+        };
+        Some(AuditEntry {
+            timestamp: field("timestamp"),
+            user: field("user"),
+            unit: field("unit"),
+            action: field("action"),
+            outcome: field("outcome"),
+            error: field("error"),
+        })
+    }
+}
+
+/// The result of an operation along with the error writing it to the audit log, if it could not be recorded.
+pub struct Audited<T> {
+    pub result: T,
+    pub audit_error: Option<std::io::Error>,
+}
+
+impl<T> Audited<T> {
+    /// Records the entries of an operation that has already happened, keeping a write error for the caller.
+    pub fn record(result: T, entries: &[AuditEntry]) -> Self {
+        Audited {
+            result,
+            audit_error: record(entries).err(),
+        }
+    }
+}
+
+/// The entries of the audit log along with the number of lines that are no entries, e.g. cut off by a crash.
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+    pub skipped_lines: usize,
+}
+
+/// Location of the audit log, one JSON object per line, in the user's data directory.
+pub fn log_path() -> PathBuf {
+    glib::user_data_dir()
+        .join("systemd-gtk")
+        .join("audit.jsonl")
+}
+
+/// Appends the entries to the audit log, creating it if necessary.
+///
+/// # Errors
+/// - Returns an error if the log could not be written.
+pub fn record(entries: &[AuditEntry]) -> std::io::Result<()> {
+    let path = log_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for entry in entries {
+        writeln!(file, "{}", entry.to_json())?;
+    }
+    Ok(())
+}
+
+/// Reads all entries of the audit log, oldest first, counting the lines that are no valid entries.
+///
+/// # Errors
+/// - Returns an error if the log exists but could not be read.
+pub fn read() -> std::io::Result<AuditLog> {
+    let mut log = AuditLog {
+        entries: vec![],
+        skipped_lines: 0,
+    };
+    let file = match File::open(log_path()) {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(log),
+        Err(error) => return Err(error),
+    };
+    for line in BufReader::new(file).lines() {
+        match AuditEntry::from_json(&line?) {
+            Some(entry) => log.entries.push(entry),
+            None => log.skipped_lines += 1,
+        }
+    }
+    Ok(log)
+}
+
+/// Exports the entries of the audit log as JSON Lines to the given file, without the lines that are no entries.
+///
+/// # Errors
+/// - Returns an error if the log could not be read or the file could not be written.
+pub fn export(destination: &Path) -> std::io::Result<()> {
+    let mut file = File::create(destination)?;
+    for entry in read()?.entries {
+        writeln!(file, "{}", entry.to_json())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use adw::gio::ListStore;
+use adw::glib;
+use adw::glib::Properties;
+use adw::prelude::ObjectExt;
+use adw::subclass::prelude::*;
+use std::cell::{OnceCell, RefCell};
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::CgroupEntry)]
+pub struct CgroupEntry {
+    /// The control group name, or the executable name of a process.
+    #[property(get, construct_only)]
+    name: RefCell<String>,
+
+    /// The process id, `0` for control groups.
+    #[property(get, construct_only)]
+    pid: RefCell<u32>,
+
+    #[property(get, construct_only)]
+    user: RefCell<String>,
+
+    #[property(get, construct_only)]
+    command_line: RefCell<String>,
+
+    /// The unit owning the control group, inherited from the parent group if the group is not a unit itself.
+    #[property(get, construct_only)]
+    unit_name: RefCell<String>,
+
+    /// Child groups followed by the processes, `None` for processes.
+    pub children: OnceCell<ListStore>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for CgroupEntry {
+    const NAME: &'static str = "CgroupEntry";
+    type Type = super::CgroupEntry;
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for CgroupEntry {}
@@ -0,0 +1,54 @@
+mod imp;
+
+use crate::process::Process;
+use crate::systemd::cgroup::Cgroup;
+use adw::gio::ListStore;
+use adw::glib;
+use adw::glib::Object;
+use adw::subclass::prelude::ObjectSubclassIsExt;
+
+glib::wrapper! {
+    pub struct CgroupEntry(ObjectSubclass<imp::CgroupEntry>);
+}
+
+impl CgroupEntry {
+    /// Creates the entry of the control group and, recursively, of its child groups and processes.
+    pub fn from_cgroup(cgroup: &Cgroup, parent_unit_name: &str) -> Self {
+        let unit_name = cgroup
+            .unit_name()
+            .unwrap_or_else(|| parent_unit_name.to_string());
+        let entry: CgroupEntry = Object::builder()
+            .property("name", &cgroup.name)
+            .property("unit_name", &unit_name)
+            .build();
+
+        let children = ListStore::new::<CgroupEntry>();
+        for child in &cgroup.children {
+            children.append(&CgroupEntry::from_cgroup(child, &unit_name));
+        }
+        for process in &cgroup.processes {
+            children.append(&CgroupEntry::from_process(process, &unit_name));
+        }
+        entry
+            .imp()
+            .children
+            .set(children)
+            .expect("The children of a control group are only set once.");
+        entry
+    }
+
+    fn from_process(process: &Process, unit_name: &str) -> Self {
+        Object::builder()
+            .property("name", &process.name)
+            .property("pid", process.pid)
+            .property("user", &process.user)
+            .property("command_line", &process.command_line)
+            .property("unit_name", unit_name)
+            .build()
+    }
+
+    /// The child groups and processes, `None` for processes.
+    pub fn children(&self) -> Option<ListStore> {
+        self.imp().children.get().cloned()
+    }
+}
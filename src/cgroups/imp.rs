@@ -0,0 +1,60 @@
+use adw::glib::subclass::InitializingObject;
+use adw::subclass::prelude::*;
+use adw::{glib, StatusPage};
+use gtk::{Button, ColumnView, CompositeTemplate, Label, Stack};
+
+// Object holding the state
+#[derive(CompositeTemplate, Default)]
+#[template(resource = "/com/journeycorner/systemd-gtk/cgroups.xml")]
+pub struct CgroupBrowser {
+    #[template_child]
+    pub summary_label: TemplateChild<Label>,
+
+    #[template_child]
+    pub reload_button: TemplateChild<Button>,
+
+    #[template_child]
+    pub stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub column_view: TemplateChild<ColumnView>,
+
+    #[template_child]
+    pub error_page: TemplateChild<StatusPage>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for CgroupBrowser {
+    // `NAME` needs to match `class` attribute of template
+    const NAME: &'static str = "CgroupBrowser";
+    type Type = super::CgroupBrowser;
+    type ParentType = adw::Bin;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+    }
+
+    fn instance_init(obj: &InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+// Trait shared by all GObjects
+impl ObjectImpl for CgroupBrowser {
+    fn constructed(&self) {
+        // Call "constructed" on parent
+        self.parent_constructed();
+
+        // Setup
+        let obj = self.obj();
+        obj.setup_column_view();
+        obj.setup_reload_button();
+    }
+}
+
+// Trait shared by all widgets
+impl WidgetImpl for CgroupBrowser {}
+
+// Trait shared by all bins
+impl BinImpl for CgroupBrowser {}
@@ -0,0 +1,221 @@
+mod entry;
+mod imp;
+
+use crate::systemd::cgroup::{self, Cgroup};
+use crate::window;
+use adw::glib::{clone, Object};
+use adw::prelude::{Cast, CastNone, ListModelExt, ToVariant, WidgetExt};
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use adw::{gio, glib};
+use entry::CgroupEntry;
+use gtk::pango::EllipsizeMode;
+use gtk::prelude::{ButtonExt, ListItemExt};
+use gtk::{
+    ColumnViewColumn, Label, ListItem, SignalListItemFactory, SingleSelection, TreeExpander,
+    TreeListModel, TreeListRow,
+};
+
+glib::wrapper! {
+    pub struct CgroupBrowser(ObjectSubclass<imp::CgroupBrowser>)
+        @extends adw::Bin, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl CgroupBrowser {
+    /// Reads the control group hierarchy in the background and shows it with the root group expanded.
+    pub fn reload(&self) {
+        let imp = self.imp();
+        if imp.column_view.model().is_none() {
+            imp.stack.set_visible_child_name("loading");
+        }
+        imp.reload_button.set_sensitive(false);
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = browser)]
+            self,
+            async move {
+                let cgroup_tree = gio::spawn_blocking(cgroup::cgroup_tree)
+                    .await
+                    .expect("The lookup needs to finish.");
+                let imp = browser.imp();
+                imp.reload_button.set_sensitive(true);
+                match cgroup_tree {
+                    Ok(root) => browser.show_tree(&root),
+                    Err(error) => {
+                        imp.error_page.set_description(Some(&error.to_string()));
+                        imp.stack.set_visible_child_name("error");
+                    }
+                }
+            }
+        ));
+    }
+
+    fn show_tree(&self, root: &Cgroup) {
+        let imp = self.imp();
+        let (groups, processes) = count(root);
+        imp.summary_label
+            .set_label(&format!("{groups} control groups · {processes} processes"));
+
+        let root_store = gio::ListStore::new::<CgroupEntry>();
+        root_store.append(&CgroupEntry::from_cgroup(root, ""));
+        let tree = TreeListModel::new(root_store, false, false, |item| {
+            item.downcast_ref::<CgroupEntry>()
+                .and_then(CgroupEntry::children)
+                .filter(|children| children.n_items() > 0)
+                .map(|children| children.upcast())
+        });
+        if let Some(root_row) = tree.row(0) {
+            root_row.set_expanded(true);
+        }
+
+        let selection = SingleSelection::new(Some(tree));
+        selection.set_autoselect(false);
+        selection.set_can_unselect(true);
+        selection.set_selected(gtk::INVALID_LIST_POSITION);
+        // selecting a group or process selects its unit in the unit table
+        selection.connect_selected_item_notify(clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |selection| {
+                if let Some(entry) = selection.selected_item().and_then(|item| entry_of(&item)) {
+                    browser.activate_unit_action("win.unit-select", &entry);
+                }
+            }
+        ));
+        imp.column_view.set_model(Some(&selection));
+        imp.stack.set_visible_child_name("content");
+    }
+
+    fn setup_column_view(&self) {
+        let column_view = &self.imp().column_view;
+        let name_column = ColumnViewColumn::new(Some("CONTROL GROUP"), Some(name_factory()));
+        name_column.set_resizable(true);
+        name_column.set_expand(true);
+        column_view.append_column(&name_column);
+        for (title, getter) in [
+            ("PID", pid_of as fn(&CgroupEntry) -> String),
+            ("USER", CgroupEntry::user),
+            ("COMMAND LINE", CgroupEntry::command_line),
+        ] {
+            let column = ColumnViewColumn::new(Some(title), Some(label_factory(getter)));
+            column.set_resizable(true);
+            column.set_expand(title == "COMMAND LINE");
+            column_view.append_column(&column);
+        }
+
+        // activating a row opens the details of its unit
+        column_view.connect_activate(clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |column_view, position| {
+                let entry = column_view
+                    .model()
+                    .and_then(|model| model.item(position))
+                    .and_then(|item| entry_of(&item));
+                if let Some(entry) = entry {
+                    browser.activate_unit_action("win.unit-view", &entry);
+                }
+            }
+        ));
+    }
+
+    fn setup_reload_button(&self) {
+        self.imp().reload_button.connect_clicked(clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |_| browser.reload()
+        ));
+    }
+
+    fn activate_unit_action(&self, action_name: &str, entry: &CgroupEntry) {
+        let unit_name = entry.unit_name();
+        if unit_name.is_empty() {
+            return;
+        }
+        if let Err(error) = self.activate_action(action_name, Some(&unit_name.to_variant())) {
+            window::toast(self, &format!("Could not show {unit_name}: {error}"));
+        }
+    }
+}
+
+/// Counts the control groups and processes in the hierarchy.
+fn count(cgroup: &Cgroup) -> (usize, usize) {
+    cgroup.children.iter().map(count).fold(
+        (1, cgroup.processes.len()),
+        |(groups, processes), (child_groups, child_processes)| {
+            (groups + child_groups, processes + child_processes)
+        },
+    )
+}
+
+fn entry_of(item: &Object) -> Option<CgroupEntry> {
+    item.downcast_ref::<TreeListRow>()
+        .and_then(|row| row.item())
+        .and_downcast::<CgroupEntry>()
+}
+
+fn pid_of(entry: &CgroupEntry) -> String {
+    match entry.pid() {
+        0 => String::new(),
+        pid => pid.to_string(),
+    }
+}
+
+fn name_factory() -> SignalListItemFactory {
+    let factory = SignalListItemFactory::new();
+    factory.connect_setup(|_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem");
+        let label = Label::new(None);
+        label.set_xalign(0.0);
+        label.set_ellipsize(EllipsizeMode::Middle);
+        let expander = TreeExpander::new();
+        expander.set_child(Some(&label));
+        list_item.set_child(Some(&expander));
+    });
+    factory.connect_bind(|_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem");
+        let expander = list_item
+            .child()
+            .and_downcast::<TreeExpander>()
+            .expect("The child has to be a `TreeExpander`.");
+        let row = list_item.item().and_downcast::<TreeListRow>();
+        expander.set_list_row(row.as_ref());
+        let label = expander
+            .child()
+            .and_downcast::<Label>()
+            .expect("The child has to be a `Label`.");
+        let entry = list_item.item().and_then(|item| entry_of(&item));
+        label.set_label(&entry.map(|entry| entry.name()).unwrap_or_default());
+    });
+    factory
+}
+
+fn label_factory(getter: fn(&CgroupEntry) -> String) -> SignalListItemFactory {
+    let factory = SignalListItemFactory::new();
+    factory.connect_setup(|_, list_item| {
+        let label = Label::new(None);
+        label.set_xalign(0.0);
+        label.set_ellipsize(EllipsizeMode::End);
+        list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem")
+            .set_child(Some(&label));
+    });
+    factory.connect_bind(move |_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem");
+        let label = list_item
+            .child()
+            .and_downcast::<Label>()
+            .expect("The child has to be a `Label`.");
+        let entry = list_item.item().and_then(|item| entry_of(&item));
+        let text = entry.as_ref().map(getter).unwrap_or_default();
+        label.set_tooltip_text(Some(text.as_str()).filter(|text| !text.is_empty()));
+        label.set_label(&text);
+    });
+    factory
+}
@@ -0,0 +1,225 @@
+use crate::json;
+use crate::systemd;
+use crate::systemd::unit::UnitObject;
+use crate::systemd::{JobResult, SystemCtrlAction};
+use adw::glib::{OptionArg, OptionFlags, Uri, UriFlags, VariantDict};
+use adw::prelude::{ApplicationExt, FileExt};
+use adw::{gio, glib, Application};
+use strum::IntoEnumIterator;
+
+/// Scheme of URIs opening a unit, e.g. `systemd-gtk://unit/foo.service?view=logs`.
+const URI_SCHEME: &str = "systemd-gtk";
+
+/// Exit code signaling `GApplication` to continue with the GUI.
+const CONTINUE: i32 = -1;
+
+/// Options refining `--list`, which are rejected on their own.
+const LIST_OPTIONS: [&str; 3] = ["state", "type", "json"];
+
+/// Registers the command line options, which are handled without opening a window.
+///
+/// ```
+/// systemd-gtk --list [--state STATE] [--type TYPE] [--json]
+/// systemd-gtk --restart foo.service [--restart bar.service]
+/// ```
+pub fn setup_options(app: &Application) {
+    app.add_main_option(
+        "list",
+        glib::Char::from(b'l'),
+        OptionFlags::NONE,
+        OptionArg::None,
+        "List units and exit",
+        None,
+    );
+    app.add_main_option(
+        "state",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::String,
+        "List only units in this load, active or sub state, e.g. failed",
+        Some("STATE"),
+    );
+    app.add_main_option(
+        "type",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::String,
+        "List only units of this type, e.g. service",
+        Some("TYPE"),
+    );
+    app.add_main_option(
+        "json",
+        glib::Char::from(0),
+        OptionFlags::NONE,
+        OptionArg::None,
+        "Print the list as JSON",
+        None,
+    );
+    for action in SystemCtrlAction::iter() {
+        app.add_main_option(
+            action.verb(),
+            glib::Char::from(0),
+            OptionFlags::NONE,
+            OptionArg::StringArray,
+            &format!("{action} the unit and exit, may be repeated"),
+            Some("UNIT"),
+        );
+    }
+}
+
+/// Lists units or runs actions if requested on the command line.
+///
+/// # Returns
+/// - The exit code, or `-1` to continue starting the GUI.
+pub fn handle_options(options: &VariantDict) -> i32 {
+    let mut handled = false;
+    let mut exit_code = 0;
+
+    if !options.contains("list") {
+        if let Some(option) = LIST_OPTIONS
+            .into_iter()
+            .find(|option| options.contains(option))
+        {
+            eprintln!("--{option} can only be used with --list");
+            return 1;
+        }
+    }
+
+    for action in SystemCtrlAction::iter() {
+        let unit_names = options
+            .lookup::<Vec<String>>(action.verb())
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        if unit_names.is_empty() {
+            continue;
+        }
+        handled = true;
+        let audited = action.run(&unit_names);
+        let results = audited.result;
+        for (unit_name, result) in unit_names.iter().zip(&results) {
+            println!("{action} {unit_name}: {result}");
+        }
+        if let Some(error) = audited.audit_error {
+            eprintln!("Could not write the audit log: {error}");
+        }
+        if results.iter().any(|result| *result != JobResult::Done) {
+            exit_code = 1;
+        }
+    }
+
+    if options.contains("list") {
+        handled = true;
+        let state = options.lookup::<String>("state").ok().flatten();
+        let unit_type = options.lookup::<String>("type").ok().flatten();
+        match systemd::units() {
+            Ok(units) => {
+                let units = units
+                    .into_iter()
+                    .filter(|unit| matches_state(unit, state.as_deref()))
+                    .filter(|unit| matches_type(unit, unit_type.as_deref()))
+                    .collect::<Vec<_>>();
+                if options.contains("json") {
+                    print_json(&units);
+                } else {
+                    print_table(&units);
+                }
+            }
+            Err(error) => {
+                eprintln!("Could not list the units: {error}");
+                exit_code = 1;
+            }
+        }
+    }
+
+    if handled {
+        exit_code
+    } else {
+        CONTINUE
+    }
+}
+
+/// Returns the unit and optional view (`file`, `properties` or `logs`) to open for a command line argument.
+///
+/// `GApplication` passes plain unit names as files relative to the working directory, so their base name is used.
+pub fn unit_to_open(file: &gio::File) -> Option<(String, Option<String>)> {
+    if !file.has_uri_scheme(URI_SCHEME) {
+        let unit_name = file.basename()?.to_string_lossy().to_string();
+        return Some((unit_name, None));
+    }
+
+    let uri = Uri::parse(&file.uri(), UriFlags::NONE).ok()?;
+    if uri.host().as_deref() != Some("unit") {
+        return None;
+    }
+    let unit_name = uri.path().trim_start_matches('/').to_string();
+    let view = uri.query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|parameter| parameter.strip_prefix("view="))
+            .map(str::to_string)
+    });
+    (!unit_name.is_empty()).then_some((unit_name, view))
+}
+
+/// Matches like `systemctl --state`, which accepts load, active and sub states.
+fn matches_state(unit: &UnitObject, state: Option<&str>) -> bool {
+    match state {
+        Some(state) => [unit.load(), unit.state(), unit.sub_state()].contains(&state.to_string()),
+        None => true,
+    }
+}
+
+fn matches_type(unit: &UnitObject, unit_type: Option<&str>) -> bool {
+    match unit_type {
+        Some(unit_type) => systemd::unit::unit_type(&unit.unit_name()) == unit_type,
+        None => true,
+    }
+}
+
+fn print_table(units: &[UnitObject]) {
+    let width = |getter: fn(&UnitObject) -> String, title: &str| {
+        units
+            .iter()
+            .map(|unit| getter(unit).chars().count())
+            .chain([title.len()])
+            .max()
+            .unwrap_or_default()
+    };
+    let unit_width = width(UnitObject::unit_name, "UNIT");
+    let load_width = width(UnitObject::load, "LOAD");
+    let state_width = width(UnitObject::state, "ACTIVE");
+    let sub_width = width(UnitObject::sub_state, "SUB");
+
+    println!(
+        "{:unit_width$} {:load_width$} {:state_width$} {:sub_width$} DESCRIPTION",
+        "UNIT", "LOAD", "ACTIVE", "SUB"
+    );
+    for unit in units {
+        println!(
+            "{:unit_width$} {:load_width$} {:state_width$} {:sub_width$} {}",
+            unit.unit_name(),
+            unit.load(),
+            unit.state(),
+            unit.sub_state(),
+            unit.description()
+        );
+    }
+}
+
+/// Prints the units using the same field names as `systemctl list-units --output=json`.
+fn print_json(units: &[UnitObject]) {
+    let objects = units
+        .iter()
+        .map(|unit| {
+            json::object(&[
+                ("unit", &unit.unit_name()),
+                ("load", &unit.load()),
+                ("active", &unit.state()),
+                ("sub", &unit.sub_state()),
+                ("description", &unit.description()),
+            ])
+        })
+        .collect::<Vec<_>>();
+    println!("[{}]", objects.join(",\n"));
+}
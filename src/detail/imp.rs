@@ -0,0 +1,113 @@
+use crate::metrics::graphs::ResourceGraphs;
+use crate::process::list::ProcessList;
+use crate::status::StatusCard;
+use crate::systemd::FragmentPath;
+use adw::glib::subclass::InitializingObject;
+use adw::prelude::{FromVariant, StaticTypeExt, StaticVariantType};
+use adw::subclass::prelude::*;
+use adw::{glib, ViewStack, WindowTitle};
+use gtk::{CompositeTemplate, Stack, TextView};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+// Object holding the state
+#[derive(CompositeTemplate, Default)]
+#[template(resource = "/com/journeycorner/systemd-gtk/detail.xml")]
+pub struct UnitDetail {
+    #[template_child]
+    pub window_title: TemplateChild<WindowTitle>,
+
+    #[template_child]
+    pub view_stack: TemplateChild<ViewStack>,
+
+    #[template_child]
+    pub status_card: TemplateChild<StatusCard>,
+
+    #[template_child]
+    pub file_stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub file_text_view: TemplateChild<TextView>,
+
+    #[template_child]
+    pub properties_stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub properties_text_view: TemplateChild<TextView>,
+
+    #[template_child]
+    pub logs_stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub logs_text_view: TemplateChild<TextView>,
+
+    #[template_child]
+    pub dependencies_stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub dependencies_text_view: TemplateChild<TextView>,
+
+    #[template_child]
+    pub process_list: TemplateChild<ProcessList>,
+
+    #[template_child]
+    pub resource_graphs: TemplateChild<ResourceGraphs>,
+
+    /// Name of the unit currently shown.
+    pub unit_name: RefCell<String>,
+
+    /// Views already loaded for the current unit, the others are loaded once they are shown.
+    pub loaded_views: RefCell<HashSet<String>>,
+
+    /// Unit file contents by unit name, only valid as long as the unit file was not modified.
+    pub unit_file_cache: RefCell<HashMap<String, (FragmentPath, String)>>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for UnitDetail {
+    // `NAME` needs to match `class` attribute of template
+    const NAME: &'static str = "UnitDetail";
+    type Type = super::UnitDetail;
+    type ParentType = adw::Bin;
+
+    fn class_init(klass: &mut Self::Class) {
+        ProcessList::ensure_type();
+        ResourceGraphs::ensure_type();
+        StatusCard::ensure_type();
+        klass.bind_template();
+
+        // e.g. `detail.view('logs')`, used by the links in the views
+        klass.install_action(
+            "detail.view",
+            Some(&String::static_variant_type()),
+            |detail, _, parameter| {
+                let view = parameter
+                    .and_then(String::from_variant)
+                    .expect("The action parameter needs to be a view name.");
+                detail.imp().view_stack.set_visible_child_name(&view);
+            },
+        );
+    }
+
+    fn instance_init(obj: &InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+// Trait shared by all GObjects
+impl ObjectImpl for UnitDetail {
+    fn constructed(&self) {
+        // Call "constructed" on parent
+        self.parent_constructed();
+
+        // Setup
+        self.obj().setup_view_stack();
+    }
+}
+
+// Trait shared by all widgets
+impl WidgetImpl for UnitDetail {}
+
+// Trait shared by all bins
+impl BinImpl for UnitDetail {}
@@ -0,0 +1,206 @@
+mod imp;
+
+use crate::metrics::Histories;
+use crate::systemd;
+use adw::glib::clone;
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use adw::{gio, glib};
+use gtk::prelude::{TextBufferExt, TextViewExt};
+use gtk::{Stack, TextView};
+
+/// Number of journal entries shown in the logs view.
+const JOURNAL_LINES: u32 = 200;
+
+glib::wrapper! {
+    pub struct UnitDetail(ObjectSubclass<imp::UnitDetail>)
+        @extends adw::Bin, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl UnitDetail {
+    /// Shows the given unit, switching to `view` (`status`, `file`, `properties`, `logs`, `dependencies`, `processes` or
+    /// `resources`) if it names a view.
+    ///
+    /// Views are loaded in the background once they become visible.
+    pub fn show_unit(&self, unit_name: &str, view: Option<&str>) {
+        let imp = self.imp();
+        imp.unit_name.replace(unit_name.to_string());
+        imp.loaded_views.borrow_mut().clear();
+        imp.window_title.set_title(unit_name);
+        imp.window_title.set_subtitle("");
+
+        if let Some(view) = view.filter(|view| imp.view_stack.child_by_name(view).is_some()) {
+            imp.view_stack.set_visible_child_name(view);
+        }
+        self.load_visible_view();
+    }
+
+    /// Loads the visible view again if one of the units is shown, e.g. after an action ran for it.
+    ///
+    /// The status is refreshed in place, the other views are loaded again once they become visible.
+    pub fn reload_unit(&self, unit_names: &[String]) {
+        let imp = self.imp();
+        if !unit_names.contains(&imp.unit_name.borrow()) {
+            return;
+        }
+        let status_loaded = imp.loaded_views.borrow().contains("status");
+        imp.loaded_views.borrow_mut().clear();
+        if status_loaded {
+            imp.loaded_views.borrow_mut().insert(String::from("status"));
+            imp.status_card.refresh();
+        }
+        self.load_visible_view();
+    }
+
+    /// Sets the resource usage histories of the watched units, drawn in the resources view.
+    pub fn set_resource_histories(&self, histories: Histories) {
+        self.imp().resource_graphs.set_histories(histories);
+    }
+
+    /// Redraws the resource graphs after the watched units were sampled.
+    pub fn update_resource_graphs(&self) {
+        self.imp().resource_graphs.update();
+    }
+
+    fn setup_view_stack(&self) {
+        self.imp()
+            .view_stack
+            .connect_visible_child_name_notify(clone!(
+                #[weak(rename_to = detail)]
+                self,
+                move |_| detail.load_visible_view()
+            ));
+    }
+
+    fn load_visible_view(&self) {
+        let imp = self.imp();
+        let Some(view) = imp.view_stack.visible_child_name() else {
+            return;
+        };
+        let unit_name = imp.unit_name.borrow().clone();
+        if unit_name.is_empty() || !imp.loaded_views.borrow_mut().insert(view.to_string()) {
+            return;
+        }
+
+        match view.as_str() {
+            "status" => imp.status_card.show_unit(&unit_name),
+            "file" => self.load_unit_file(unit_name),
+            "properties" => self.load_text(
+                &imp.properties_stack,
+                &imp.properties_text_view,
+                unit_name,
+                systemd::show_all,
+            ),
+            "logs" => self.load_text(
+                &imp.logs_stack,
+                &imp.logs_text_view,
+                unit_name,
+                |unit_name| systemd::journal(unit_name, JOURNAL_LINES),
+            ),
+            "dependencies" => self.load_text(
+                &imp.dependencies_stack,
+                &imp.dependencies_text_view,
+                unit_name,
+                systemd::dependencies,
+            ),
+            "processes" => imp.process_list.show_unit(&unit_name),
+            "resources" => imp.resource_graphs.show_unit(&unit_name),
+            _ => {}
+        }
+    }
+
+    /// Runs `load` off the main thread and shows its output in the text view.
+    fn load_text(
+        &self,
+        stack: &Stack,
+        text_view: &TextView,
+        unit_name: String,
+        load: fn(&str) -> std::io::Result<String>,
+    ) {
+        stack.set_visible_child_name("loading");
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = detail)]
+            self,
+            #[weak]
+            stack,
+            #[weak]
+            text_view,
+            async move {
+                let load_unit_name = unit_name.clone();
+                let content = gio::spawn_blocking(move || load(&load_unit_name))
+                    .await
+                    .expect("The lookup needs to finish.");
+                if !detail.shows_unit(&unit_name) {
+                    return;
+                }
+                let text = content.unwrap_or_else(|error| format!("Could not load: {error}"));
+                text_view.buffer().set_text(&text);
+                stack.set_visible_child_name("content");
+            }
+        ));
+    }
+
+    /// Loads the unit file and its drop-ins, unless the cached content is still up to date.
+    fn load_unit_file(&self, unit_name: String) {
+        self.imp().file_stack.set_visible_child_name("loading");
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = detail)]
+            self,
+            async move {
+                let imp = detail.imp();
+                let lookup_unit_name = unit_name.clone();
+                let fragment_path =
+                    gio::spawn_blocking(move || systemd::fragment_path(&lookup_unit_name))
+                        .await
+                        .expect("The lookup needs to finish.");
+
+                let content = match fragment_path {
+                    Ok(fragment_path) => {
+                        let cached = imp
+                            .unit_file_cache
+                            .borrow()
+                            .get(&unit_name)
+                            .filter(|(cached_path, _)| {
+                                fragment_path.is_cacheable() && *cached_path == fragment_path
+                            })
+                            .map(|(_, content)| content.clone());
+                        match cached {
+                            Some(content) => Ok((fragment_path, content)),
+                            None => {
+                                let cat_unit_name = unit_name.clone();
+                                gio::spawn_blocking(move || systemd::cat(&cat_unit_name))
+                                    .await
+                                    .expect("The lookup needs to finish.")
+                                    .map(|content| (fragment_path, content))
+                            }
+                        }
+                    }
+                    Err(error) => Err(error),
+                };
+                if !detail.shows_unit(&unit_name) {
+                    return;
+                }
+
+                match content {
+                    Ok((fragment_path, content)) => {
+                        imp.window_title.set_subtitle(&fragment_path.path);
+                        imp.file_text_view.buffer().set_text(&content);
+                        imp.unit_file_cache
+                            .borrow_mut()
+                            .insert(unit_name, (fragment_path, content));
+                    }
+                    Err(error) => imp
+                        .file_text_view
+                        .buffer()
+                        .set_text(&format!("Could not read unit file: {error}")),
+                }
+                imp.file_stack.set_visible_child_name("content");
+            }
+        ));
+    }
+
+    /// Whether the unit is still shown, it may have changed while a view was loading.
+    pub fn shows_unit(&self, unit_name: &str) -> bool {
+        *self.imp().unit_name.borrow() == unit_name
+    }
+}
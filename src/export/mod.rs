@@ -0,0 +1,131 @@
+use crate::json;
+use std::path::{Path, PathBuf};
+
+/// File formats the unit table can be exported to.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [
+        ExportFormat::Csv,
+        ExportFormat::Json,
+        ExportFormat::Markdown,
+    ];
+
+    /// Name of the format, also the name of its file filter in the save dialog.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+        }
+    }
+
+    /// Picks the format by file extension, `None` if the extension is missing or unknown.
+    pub fn from_path(path: &Path) -> Option<ExportFormat> {
+        match path.extension().and_then(|extension| extension.to_str())? {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ExportFormat> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    /// Picks the format by file extension, or else uses the given format and appends its extension to the path.
+    pub fn resolve(path: PathBuf, fallback: ExportFormat) -> (ExportFormat, PathBuf) {
+        match Self::from_path(&path) {
+            Some(format) => (format, path),
+            None => {
+                let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+                file_name.push(".");
+                file_name.push(fallback.extension());
+                (fallback, path.with_file_name(file_name))
+            }
+        }
+    }
+
+    /// Formats the table with the given column headers.
+    pub fn format(&self, headers: &[String], rows: &[Vec<String>]) -> String {
+        match self {
+            ExportFormat::Csv => to_csv(headers, rows),
+            ExportFormat::Json => to_json(headers, rows),
+            ExportFormat::Markdown => to_markdown(headers, rows),
+        }
+    }
+}
+
+/// Formats as RFC 4180 CSV, quoting fields only where necessary.
+fn to_csv(headers: &[String], rows: &[Vec<String>]) -> String {
+    let line = |fields: &[String]| {
+        fields
+            .iter()
+            .map(|field| {
+                if field.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut csv = line(headers) + "\r\n";
+    for row in rows {
+        csv.push_str(&line(row));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Formats as JSON array of objects, keyed by the lowercase column header.
+fn to_json(headers: &[String], rows: &[Vec<String>]) -> String {
+    let keys = headers
+        .iter()
+        .map(|header| header.to_lowercase())
+        .collect::<Vec<_>>();
+    let objects = rows
+        .iter()
+        .map(|row| {
+            let fields = keys
+                .iter()
+                .map(String::as_str)
+                .zip(row.iter().map(String::as_str))
+                .collect::<Vec<_>>();
+            json::object(&fields)
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]\n", objects.join(",\n"))
+}
+
+/// Formats as GitHub flavored Markdown table.
+fn to_markdown(headers: &[String], rows: &[Vec<String>]) -> String {
+    let line = |fields: &[String]| {
+        let cells = fields
+            .iter()
+            .map(|field| field.replace('|', "\\|").replace('\n', " "))
+            .collect::<Vec<_>>();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut markdown = line(headers);
+    markdown.push_str(&line(&vec![String::from("---"); headers.len()]));
+    for row in rows {
+        markdown.push_str(&line(row));
+    }
+    markdown
+}
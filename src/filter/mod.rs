@@ -0,0 +1,133 @@
+use crate::query::Query;
+use crate::systemd::unit::{unit_type, UnitObject};
+use crate::systemd::State;
+use adw::gio;
+use adw::prelude::SettingsExtManual;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Settings key of the saved filters.
+pub const SAVED_FILTERS_KEY: &str = "saved-filters";
+
+/// Unit types offered as quick filter chips.
+pub const UNIT_TYPES: [&str; 11] = [
+    "service",
+    "socket",
+    "timer",
+    "mount",
+    "target",
+    "device",
+    "path",
+    "slice",
+    "scope",
+    "swap",
+    "automount",
+];
+
+/// States offered as quick filter chips, `transitional` covers all states between active and inactive.
+pub const STATES: [&str; 4] = ["active", "inactive", "failed", "transitional"];
+
+/// Everything the unit table is filtered by: the search query and the toggled chips.
+///
+/// Chips of the same group are alternatives, a group without toggled chips does not filter at all.
+#[derive(Default)]
+pub struct UnitFilter {
+    pub query: Query,
+    pub types: HashSet<String>,
+    pub states: HashSet<String>,
+}
+
+impl UnitFilter {
+    pub fn matches(&self, unit_object: &UnitObject) -> bool {
+        self.matches_type(unit_object)
+            && self.matches_state(unit_object)
+            && self.query.matches(unit_object)
+    }
+
+    fn matches_type(&self, unit_object: &UnitObject) -> bool {
+        if self.types.is_empty() {
+            return true;
+        }
+        let unit_name = unit_object.unit_name();
+        self.types.contains(unit_type(&unit_name))
+    }
+
+    fn matches_state(&self, unit_object: &UnitObject) -> bool {
+        if self.states.is_empty() {
+            return true;
+        }
+        let state = unit_object.state();
+        let transitional = State::from_str(&state).is_ok_and(|state| state.is_transitional());
+        self.states.contains(&state) || (transitional && self.states.contains("transitional"))
+    }
+}
+
+/// A named search query with its toggled chips, persisted in the settings.
+pub struct SavedFilter {
+    pub name: String,
+    pub query: String,
+    pub chips: Vec<String>,
+}
+
+impl SavedFilter {
+    /// Loads the saved filters in the order they were saved.
+    pub fn load(settings: &gio::Settings) -> Vec<SavedFilter> {
+        settings
+            .get::<Vec<(String, String, Vec<String>, Vec<String>)>>(SAVED_FILTERS_KEY)
+            .into_iter()
+            .map(|(name, query, types, states)| SavedFilter {
+                name,
+                query,
+                chips: types.into_iter().chain(states).collect(),
+            })
+            .collect()
+    }
+
+    /// Saves the filter, replacing a saved filter of the same name.
+    pub fn save(&self, settings: &gio::Settings) -> Result<(), String> {
+        let mut saved_filters = Self::load(settings);
+        match saved_filters
+            .iter_mut()
+            .find(|saved_filter| saved_filter.name == self.name)
+        {
+            Some(saved_filter) => {
+                saved_filter.query = self.query.clone();
+                saved_filter.chips = self.chips.clone();
+            }
+            None => saved_filters.push(SavedFilter {
+                name: self.name.clone(),
+                query: self.query.clone(),
+                chips: self.chips.clone(),
+            }),
+        }
+        Self::store(settings, &saved_filters)
+    }
+
+    pub fn delete(settings: &gio::Settings, name: &str) -> Result<(), String> {
+        let mut saved_filters = Self::load(settings);
+        saved_filters.retain(|saved_filter| saved_filter.name != name);
+        Self::store(settings, &saved_filters)
+    }
+
+    fn store(settings: &gio::Settings, saved_filters: &[SavedFilter]) -> Result<(), String> {
+        let value = saved_filters
+            .iter()
+            .map(|saved_filter| {
+                let (types, states) = saved_filter
+                    .chips
+                    .iter()
+                    .cloned()
+                    .partition::<Vec<_>, _>(|chip| UNIT_TYPES.contains(&chip.as_str()));
+                (
+                    saved_filter.name.clone(),
+                    saved_filter.query.clone(),
+                    types,
+                    states,
+                )
+            })
+            .collect::<Vec<_>>();
+        settings
+            .set(SAVED_FILTERS_KEY, value)
+            .map_err(|error| error.to_string())
+    }
+}
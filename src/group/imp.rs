@@ -0,0 +1,37 @@
+use adw::glib;
+use adw::glib::Properties;
+use adw::prelude::ObjectExt;
+use adw::subclass::prelude::*;
+use gtk::FilterListModel;
+use std::cell::{OnceCell, RefCell};
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::GroupObject)]
+pub struct GroupObject {
+    /// The unit type or slice shared by the units of the group.
+    #[property(get, construct_only)]
+    name: RefCell<String>,
+
+    /// Number of units in the group matching the filter.
+    #[property(get, set)]
+    count: RefCell<u32>,
+
+    /// Name, count and aggregate state, e.g. `service (42) · 3 failed`.
+    #[property(get, set)]
+    summary: RefCell<String>,
+
+    /// The filtered and sorted units of the group.
+    pub units: OnceCell<FilterListModel>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for GroupObject {
+    const NAME: &'static str = "GroupObject";
+    type Type = super::GroupObject;
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for GroupObject {}
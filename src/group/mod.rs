@@ -0,0 +1,112 @@
+mod imp;
+
+use crate::systemd::unit::{unit_type, UnitObject};
+use adw::glib::Object;
+use adw::prelude::{Cast, IsA, ListModelExt, ListModelExtManual};
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use adw::{gio, glib};
+use gtk::{CustomFilter, FilterListModel};
+use strum::{Display, EnumIter, EnumString};
+
+/// Settings key of the grouping of the unit table.
+pub const GROUPING_KEY: &str = "grouping";
+
+/// The systemd property holding the slice of a unit, loaded with the units when grouping by slice.
+pub const SLICE_PROPERTY: &str = "Slice";
+
+/// How the units in the table are grouped.
+#[derive(Debug, Display, EnumString, EnumIter, Clone, Copy, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum Grouping {
+    /// A flat list of units.
+    None,
+    /// By unit type, the suffix of the unit name.
+    Type,
+    /// By the cgroup slice the unit belongs to.
+    Slice,
+}
+
+impl Grouping {
+    /// The group the unit belongs to, `None` if the units are not grouped.
+    pub fn key(&self, unit_object: &UnitObject) -> Option<String> {
+        match self {
+            Grouping::None => None,
+            Grouping::Type => {
+                let unit_name = unit_object.unit_name();
+                let unit_type = unit_type(&unit_name);
+                Some(if unit_type.is_empty() {
+                    String::from("no type")
+                } else {
+                    unit_type.to_string()
+                })
+            }
+            Grouping::Slice => {
+                let slice = unit_object.unit_property(SLICE_PROPERTY);
+                Some(if slice.is_empty() {
+                    String::from("no slice")
+                } else {
+                    slice
+                })
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct GroupObject(ObjectSubclass<imp::GroupObject>);
+}
+
+impl GroupObject {
+    /// Creates the group of all units in `units` belonging to it, `units` usually being filtered and sorted already.
+    pub fn new(name: &str, grouping: Grouping, units: &impl IsA<gio::ListModel>) -> Self {
+        let group: GroupObject = Object::builder().property("name", name).build();
+        let group_name = name.to_string();
+        let filter = CustomFilter::new(move |obj| {
+            obj.downcast_ref::<UnitObject>()
+                .and_then(|unit_object| grouping.key(unit_object))
+                .is_some_and(|key| key == group_name)
+        });
+        let group_units = FilterListModel::new(Some(units.clone()), Some(filter));
+        group.update_summary(&group_units);
+        group_units.connect_items_changed(glib::clone!(
+            #[weak]
+            group,
+            move |group_units, _, _, _| group.update_summary(group_units)
+        ));
+        group
+            .imp()
+            .units
+            .set(group_units)
+            .expect("The units of a group are only set once.");
+        group
+    }
+
+    /// The filtered and sorted units of the group, the children of the group in the tree.
+    pub fn units(&self) -> FilterListModel {
+        self.imp()
+            .units
+            .get()
+            .expect("The units are set in `GroupObject::new`.")
+            .clone()
+    }
+
+    fn update_summary(&self, group_units: &FilterListModel) {
+        let count = group_units.n_items();
+        let states = group_units
+            .iter::<UnitObject>()
+            .flatten()
+            .map(|unit_object| unit_object.state())
+            .collect::<Vec<_>>();
+        let failed = states.iter().filter(|state| *state == "failed").count();
+        let active = states.iter().filter(|state| *state == "active").count();
+        let aggregate = if failed > 0 {
+            format!("{failed} failed")
+        } else if active == states.len() {
+            String::from("all active")
+        } else {
+            format!("{active} active")
+        };
+        self.set_count(count);
+        self.set_summary(format!("{} ({count}) · {aggregate}", self.name()));
+    }
+}
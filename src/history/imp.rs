@@ -0,0 +1,42 @@
+use adw::glib;
+use adw::glib::Properties;
+use adw::prelude::ObjectExt;
+use adw::subclass::prelude::*;
+use std::cell::RefCell;
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::HistoryEntry)]
+pub struct HistoryEntry {
+    #[property(get, construct_only)]
+    unit_name: RefCell<String>,
+
+    #[property(get, construct_only)]
+    action: RefCell<String>,
+
+    /// State of the unit before the action was run, the unit file state for enable and disable, the active state
+    /// otherwise.
+    #[property(get, construct_only)]
+    previous_state: RefCell<String>,
+
+    #[property(get, construct_only)]
+    timestamp: RefCell<String>,
+
+    #[property(get, construct_only)]
+    result: RefCell<String>,
+
+    /// `systemctl` verb reverting the action, empty if it can't be undone.
+    #[property(get, construct_only)]
+    undo_verb: RefCell<String>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for HistoryEntry {
+    const NAME: &'static str = "HistoryEntry";
+    type Type = super::HistoryEntry;
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for HistoryEntry {}
@@ -0,0 +1,64 @@
+mod imp;
+
+use crate::systemd::{JobResult, SystemCtrlAction};
+use adw::glib::Object;
+use adw::prelude::{ActionRowExt, ActionableExt, PreferencesRowExt, ToVariant};
+use adw::{glib, ActionRow};
+use gtk::prelude::WidgetExt;
+use gtk::{Align, Button};
+
+glib::wrapper! {
+    pub struct HistoryEntry(ObjectSubclass<imp::HistoryEntry>);
+}
+
+impl HistoryEntry {
+    pub fn new(
+        unit_name: &str,
+        action: SystemCtrlAction,
+        previous_state: &str,
+        result: JobResult,
+    ) -> Self {
+        // only successful actions can be undone, there is nothing to revert otherwise
+        let undo_verb = match result {
+            JobResult::Done => action.undo(previous_state).map(|undo| undo.verb()),
+            _ => None,
+        };
+        let timestamp = glib::DateTime::now_local()
+            .and_then(|now| now.format("%H:%M:%S"))
+            .map(|timestamp| timestamp.to_string())
+            .unwrap_or_default();
+        Object::builder()
+            .property("unit_name", unit_name)
+            .property("action", action.to_string())
+            .property("previous_state", previous_state)
+            .property("timestamp", timestamp)
+            .property("result", result.to_string())
+            .property("undo_verb", undo_verb.unwrap_or_default())
+            .build()
+    }
+}
+
+/// Builds the row showing a history entry, with an undo button for reversible actions.
+///
+/// Undoing dispatches the inverse action via `win.unit-*`, so it asks for confirmation like any other action.
+pub fn build_row(entry: &HistoryEntry) -> ActionRow {
+    let row = ActionRow::builder()
+        .title(format!("{} {}", entry.action(), entry.unit_name()))
+        .subtitle(format!(
+            "{} · was {} · {}",
+            entry.timestamp(),
+            entry.previous_state(),
+            entry.result()
+        ))
+        .build();
+    row.set_title_selectable(true);
+
+    if !entry.undo_verb().is_empty() {
+        let undo_button = Button::with_label("Undo");
+        undo_button.set_valign(Align::Center);
+        undo_button.set_action_name(Some(&format!("win.unit-{}", entry.undo_verb())));
+        undo_button.set_action_target_value(Some(&entry.unit_name().to_variant()));
+        row.add_suffix(&undo_button);
+    }
+    row
+}
@@ -0,0 +1,166 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Quotes and escapes a string as JSON string literal.
+pub fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats the fields as single line JSON object, keeping their order.
+pub fn object(fields: &[(&str, &str)]) -> String {
+    let members = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", quote(key), quote(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{{members}}}")
+}
+
+/// Parses a single line JSON object with string values only, as written by `object`.
+///
+/// Returns `None` for anything else, e.g. a truncated line or a value that is not a string.
+pub fn parse_object(line: &str) -> Option<Vec<(String, String)>> {
+    let mut chars = line.trim().chars().peekable();
+    let mut fields = vec![];
+    if chars.next()? != '{' {
+        return None;
+    }
+    skip_whitespace(&mut chars);
+    if chars.next_if_eq(&'}').is_none() {
+        loop {
+            skip_whitespace(&mut chars);
+            let key = parse_string(&mut chars)?;
+            skip_whitespace(&mut chars);
+            if chars.next()? != ':' {
+                return None;
+            }
+            skip_whitespace(&mut chars);
+            let value = parse_string(&mut chars)?;
+            fields.push((key, value));
+            skip_whitespace(&mut chars);
+            match chars.next()? {
+                ',' => continue,
+                '}' => break,
+                _ => return None,
+            }
+        }
+    }
+    chars.next().is_none().then_some(fields)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'u' => value.push(parse_unicode_escape(chars)?),
+                c @ ('"' | '\\' | '/') => value.push(c),
+                _ => return None,
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+/// Parses the hex digits of a `\u` escape, combining a surrogate pair like `\ud83d\ude00` into one char.
+fn parse_unicode_escape(chars: &mut Peekable<Chars>) -> Option<char> {
+    let high = parse_hex(chars)?;
+    if !(0xd800..0xdc00).contains(&high) {
+        return char::from_u32(high);
+    }
+    if chars.next()? != '\\' || chars.next()? != 'u' {
+        return None;
+    }
+    let low = parse_hex(chars)?;
+    if !(0xdc00..0xe000).contains(&low) {
+        return None;
+    }
+    char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+}
+
+fn parse_hex(chars: &mut Peekable<Chars>) -> Option<u32> {
+    let digits = (0..4).map(|_| chars.next()).collect::<Option<String>>()?;
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_escapes() {
+        let value = "say \"hi\"\\\n\r\t\u{1}/ünïcödé 🦀";
+        let line = object(&[("unit", "a.service"), ("error", value)]);
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            parse_object(&line),
+            Some(fields(&[("unit", "a.service"), ("error", value)]))
+        );
+    }
+
+    #[test]
+    fn parses_other_writers() {
+        assert_eq!(
+            parse_object(r#" { "a" : "é\b\f\/" , "b":"🦀" } "#),
+            Some(fields(&[("a", "é\u{8}\u{c}/"), ("b", "🦀")]))
+        );
+        assert_eq!(parse_object("{}"), Some(vec![]));
+        assert_eq!(parse_object("{ }"), Some(vec![]));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            "",
+            "[]",
+            r#"{"a":"b""#,
+            r#"{"a":"b"} trailing"#,
+            r#"{"a" "b"}"#,
+            r#"{"a":"b" "c":"d"}"#,
+            r#"{"a":1}"#,
+            r#"{"a":"\x"}"#,
+            r#"{"a":"\u00"}"#,
+            r#"{"a":"\ud83e"}"#,
+            r#"{"a":"\udd80"}"#,
+            r#"{"a":"b",}"#,
+        ] {
+            assert_eq!(parse_object(line), None, "{line}");
+        }
+    }
+}
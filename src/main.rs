@@ -1,3 +1,18 @@
+mod audit;
+mod cgroups;
+mod cli;
+mod detail;
+mod export;
+mod filter;
+mod group;
+mod history;
+mod json;
+mod metrics;
+mod monitor;
+mod process;
+mod query;
+mod settings;
+mod status;
 mod systemd;
 mod table;
 mod window;
@@ -14,22 +29,86 @@ fn main() -> glib::ExitCode {
         .expect("Failed to register resources.");
 
     // Build application
-    let app = Application::builder().application_id(APP_ID).build();
+    let app = Application::builder()
+        .application_id(APP_ID)
+        .flags(gio::ApplicationFlags::HANDLES_OPEN)
+        .build();
+
+    // Handle headless invocations before any window is built
+    cli::setup_options(&app);
+    app.connect_handle_local_options(|_, options| cli::handle_options(options));
 
     // Connect to signals
     app.connect_activate(build_ui);
+    app.connect_open(open_units);
     setup_shortcuts(&app);
+    setup_notification_actions(&app);
 
     // Run application
     app.run()
 }
 fn build_ui(app: &adw::Application) {
     // Create new window and present it
-    let window = Window::new(app);
-    window.present();
+    window_of(app).present_from_background();
+}
+
+/// The window of the app, which may be hidden in the background, or a new one.
+fn window_of(app: &adw::Application) -> Window {
+    app.windows()
+        .into_iter()
+        .find_map(|window| window.downcast::<Window>().ok())
+        .unwrap_or_else(|| Window::new(app))
+}
+
+/// Opens units passed as `foo.service` or `systemd-gtk://unit/foo.service?view=logs`.
+fn open_units(app: &adw::Application, files: &[gio::File], _hint: &str) {
+    // focus the existing window instead of opening another one
+    let window = window_of(app);
+    window.present_from_background();
+    for (unit_name, view) in files.iter().filter_map(cli::unit_to_open) {
+        window.open_unit(&unit_name, view.as_deref());
+    }
+}
+
+/// Actions of the notifications about watched units, taking the unit name as target.
+fn setup_notification_actions(app: &Application) {
+    let logs_action = gio::ActionEntry::builder("unit-logs")
+        .parameter_type(Some(&String::static_variant_type()))
+        .activate(|app: &Application, _, parameter| {
+            let Some(unit_name) = parameter.and_then(String::from_variant) else {
+                return;
+            };
+            let window = window_of(app);
+            window.present_from_background();
+            window.open_unit(&unit_name, Some("logs"));
+        })
+        .build();
+    let restart_action = gio::ActionEntry::builder("unit-restart")
+        .parameter_type(Some(&String::static_variant_type()))
+        .activate(|app: &Application, _, parameter| {
+            let Some(unit_name) = parameter else {
+                return;
+            };
+            // the window asks for confirmation like for any restart
+            let window = window_of(app);
+            window.present_from_background();
+            if let Err(error) =
+                WidgetExt::activate_action(&window, "win.unit-restart", Some(unit_name))
+            {
+                window::toast(&window, &format!("Could not restart the unit: {error}"));
+            }
+        })
+        .build();
+    // quits even if the window is hidden in the background
+    let quit_action = gio::ActionEntry::builder("quit")
+        .activate(|app: &Application, _, _| app.quit())
+        .build();
+    app.add_action_entries([logs_action, restart_action, quit_action]);
 }
 
 fn setup_shortcuts(app: &Application) {
     app.set_accels_for_action("win.search_bar_show", &["<Ctrl>f"]);
     app.set_accels_for_action("win.view_unit_action", &["Return"]);
+    app.set_accels_for_action("win.history_show", &["<Ctrl>h"]);
+    app.set_accels_for_action("app.quit", &["<Ctrl>q"]);
 }
@@ -0,0 +1,67 @@
+use crate::metrics::{Graph, Histories};
+use adw::glib::subclass::InitializingObject;
+use adw::subclass::prelude::*;
+use adw::{gio, glib};
+use gtk::{Button, CompositeTemplate, DrawingArea, Label, Stack};
+use std::cell::{OnceCell, RefCell};
+
+// Object holding the state
+#[derive(CompositeTemplate, Default)]
+#[template(resource = "/com/journeycorner/systemd-gtk/graphs.xml")]
+pub struct ResourceGraphs {
+    #[template_child]
+    pub stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub watch_button: TemplateChild<Button>,
+
+    #[template_child]
+    pub graphs_box: TemplateChild<gtk::Box>,
+
+    pub settings: OnceCell<gio::Settings>,
+
+    /// The sampled histories, owned and filled by the window sampling the watched units.
+    pub histories: OnceCell<Histories>,
+
+    /// Name of the unit whose graphs are shown.
+    pub unit_name: RefCell<String>,
+
+    /// Each graph with the label showing its latest values.
+    pub graphs: RefCell<Vec<(Graph, Label, DrawingArea)>>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for ResourceGraphs {
+    // `NAME` needs to match `class` attribute of template
+    const NAME: &'static str = "ResourceGraphs";
+    type Type = super::ResourceGraphs;
+    type ParentType = adw::Bin;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+    }
+
+    fn instance_init(obj: &InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+// Trait shared by all GObjects
+impl ObjectImpl for ResourceGraphs {
+    fn constructed(&self) {
+        // Call "constructed" on parent
+        self.parent_constructed();
+
+        // Setup
+        let obj = self.obj();
+        obj.setup_settings();
+        obj.setup_graphs();
+    }
+}
+
+// Trait shared by all widgets
+impl WidgetImpl for ResourceGraphs {}
+
+// Trait shared by all bins
+impl BinImpl for ResourceGraphs {}
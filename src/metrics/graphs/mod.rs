@@ -0,0 +1,196 @@
+mod imp;
+
+use crate::metrics::{Graph, Histories, HISTORY_SPAN, WATCHED_UNITS_KEY};
+use crate::settings;
+use adw::glib;
+use adw::glib::clone;
+use adw::prelude::{SettingsExt, SettingsExtManual, ToVariant};
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use gtk::cairo::Context;
+use gtk::prelude::{ActionableExt, BoxExt, DrawingAreaExt, DrawingAreaExtManual, WidgetExt};
+use gtk::{DrawingArea, Label, Orientation};
+
+/// Colours of the lines of a graph, the second one for IO writes.
+const LINE_COLORS: [(f64, f64, f64); 2] = [(0.21, 0.52, 0.89), (0.9, 0.38, 0.0)];
+
+glib::wrapper! {
+    pub struct ResourceGraphs(ObjectSubclass<imp::ResourceGraphs>)
+        @extends adw::Bin, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ResourceGraphs {
+    /// Sets the histories to draw, filled by whoever samples the watched units.
+    pub fn set_histories(&self, histories: Histories) {
+        let set = self.imp().histories.set(histories).is_ok();
+        assert!(set, "`histories` should only be set once.");
+    }
+
+    /// Shows the graphs of the unit, or a button to watch it if it is not watched.
+    pub fn show_unit(&self, unit_name: &str) {
+        let imp = self.imp();
+        imp.unit_name.replace(unit_name.to_string());
+        imp.watch_button
+            .set_action_target_value(Some(&unit_name.to_variant()));
+        self.update();
+    }
+
+    /// Redraws the graphs with the latest samples.
+    pub fn update(&self) {
+        let imp = self.imp();
+        let unit_name = imp.unit_name.borrow().clone();
+        let watched = imp
+            .settings
+            .get()
+            .is_some_and(|settings| settings.strv(WATCHED_UNITS_KEY).contains(&unit_name));
+        imp.stack
+            .set_visible_child_name(if watched { "graphs" } else { "unwatched" });
+
+        let histories = imp.histories.get().map(|histories| histories.borrow());
+        let history = histories
+            .as_ref()
+            .and_then(|histories| histories.get(&unit_name));
+        for (graph, value_label, drawing_area) in imp.graphs.borrow().iter() {
+            let latest = history
+                .map(|history| history.series(*graph))
+                .unwrap_or_default()
+                .iter()
+                .map(|points| points.last().map(|(_, value)| graph.format(*value)))
+                .collect::<Option<Vec<_>>>()
+                .filter(|latest| !latest.is_empty());
+            value_label.set_label(&match latest {
+                Some(latest) => latest.join(" / "),
+                None => String::from("No samples yet"),
+            });
+            drawing_area.queue_draw();
+        }
+    }
+
+    fn setup_settings(&self) {
+        let settings = settings::app_settings();
+        settings.connect_changed(
+            Some(WATCHED_UNITS_KEY),
+            clone!(
+                #[weak(rename_to = graphs)]
+                self,
+                move |_, _| graphs.update()
+            ),
+        );
+        self.imp()
+            .settings
+            .set(settings)
+            .expect("`settings` should not be set before calling `setup_settings`.");
+    }
+
+    fn setup_graphs(&self) {
+        let imp = self.imp();
+        for graph in Graph::ALL {
+            let title = Label::new(Some(graph.title()));
+            title.add_css_class("heading");
+            title.set_xalign(0.0);
+            title.set_hexpand(true);
+            let value_label = Label::new(None);
+            value_label.add_css_class("dim-label");
+            value_label.add_css_class("numeric");
+            let header = gtk::Box::new(Orientation::Horizontal, 6);
+            header.append(&title);
+            header.append(&value_label);
+
+            let drawing_area = DrawingArea::new();
+            drawing_area.set_content_height(120);
+            drawing_area.add_css_class("card");
+            drawing_area.set_draw_func(clone!(
+                #[weak(rename_to = graphs)]
+                self,
+                move |drawing_area, context, width, height| {
+                    graphs.draw(graph, drawing_area, context, width, height);
+                }
+            ));
+
+            let section = gtk::Box::new(Orientation::Vertical, 6);
+            section.append(&header);
+            section.append(&drawing_area);
+            imp.graphs_box.append(&section);
+            imp.graphs
+                .borrow_mut()
+                .push((graph, value_label, drawing_area));
+        }
+    }
+
+    /// Draws the last `HISTORY_SPAN` seconds of the graph, the newest sample at the right edge.
+    fn draw(
+        &self,
+        graph: Graph,
+        drawing_area: &DrawingArea,
+        context: &Context,
+        width: i32,
+        height: i32,
+    ) {
+        let (width, height) = (f64::from(width), f64::from(height));
+        let foreground = drawing_area.color();
+        let (red, green, blue) = (
+            f64::from(foreground.red()),
+            f64::from(foreground.green()),
+            f64::from(foreground.blue()),
+        );
+
+        // horizontal grid lines at quarters of the height
+        context.set_source_rgba(red, green, blue, 0.15);
+        context.set_line_width(1.0);
+        for quarter in 1..4 {
+            let y = (height * f64::from(quarter) / 4.0).round() + 0.5;
+            context.move_to(0.0, y);
+            context.line_to(width, y);
+        }
+        let _ = context.stroke();
+
+        let imp = self.imp();
+        let Some(histories) = imp.histories.get().map(|histories| histories.borrow()) else {
+            return;
+        };
+        let Some(history) = histories.get(&*imp.unit_name.borrow()) else {
+            return;
+        };
+        let series = history.series(graph);
+        let Some(newest) = series
+            .iter()
+            .filter_map(|points| points.last().map(|(time, _)| *time))
+            .reduce(f64::max)
+        else {
+            return;
+        };
+        // leave headroom above the maximum, CPU graphs show at least one percent
+        let maximum = series.iter().flatten().map(|(_, value)| *value).fold(
+            if matches!(graph, Graph::Cpu) {
+                1.0
+            } else {
+                0.0
+            },
+            f64::max,
+        ) * 1.1;
+        if maximum <= 0.0 {
+            return;
+        }
+
+        let x_of = |time: f64| width - (newest - time) / HISTORY_SPAN * width;
+        let y_of = |value: f64| height - value / maximum * height;
+        context.set_line_width(2.0);
+        for (points, (line_red, line_green, line_blue)) in series.iter().zip(LINE_COLORS) {
+            context.set_source_rgb(line_red, line_green, line_blue);
+            for (index, (time, value)) in points.iter().enumerate() {
+                if index == 0 {
+                    context.move_to(x_of(*time), y_of(*value));
+                } else {
+                    context.line_to(x_of(*time), y_of(*value));
+                }
+            }
+            let _ = context.stroke();
+        }
+
+        // the scale in the top left corner
+        context.set_source_rgba(red, green, blue, 0.6);
+        context.set_font_size(11.0);
+        context.move_to(6.0, 14.0);
+        let _ = context.show_text(&graph.format(maximum));
+    }
+}
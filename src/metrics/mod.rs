@@ -0,0 +1,211 @@
+pub mod graphs;
+
+use adw::glib;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+/// Settings key of the units whose resource usage is sampled while the app is open.
+pub const WATCHED_UNITS_KEY: &str = "watched-units";
+
+/// Seconds between two samples of the watched units.
+pub const SAMPLE_INTERVAL: u32 = 5;
+
+/// Seconds of samples kept per unit, older samples are dropped.
+pub const HISTORY_SPAN: f64 = 3600.0;
+
+/// The `systemctl show` properties sampled for watched units.
+pub const SAMPLED_PROPERTIES: [&str; 5] = [
+    "MemoryCurrent",
+    "CPUUsageNSec",
+    "IOReadBytes",
+    "IOWriteBytes",
+    "TasksCurrent",
+];
+
+/// The `systemctl show` properties sampled for watched units to notify about state changes.
+pub const STATUS_PROPERTIES: [&str; 2] = ["ActiveState", "NRestarts"];
+
+/// Resource usage histories by unit name, shared by the sampler and the graphs.
+pub type Histories = Rc<RefCell<HashMap<String, History>>>;
+
+/// The resource usage of a unit at one point in time, counters are `None` if accounting is off.
+#[derive(Clone, Copy)]
+pub struct Sample {
+    /// Monotonic time in seconds.
+    pub time: f64,
+    pub memory: Option<u64>,
+    /// CPU time consumed in nanoseconds.
+    pub cpu_usage: Option<u64>,
+    pub io_read: Option<u64>,
+    pub io_write: Option<u64>,
+    pub tasks: Option<u64>,
+}
+
+impl Sample {
+    /// Creates a sample taken now from the values of `SAMPLED_PROPERTIES`.
+    pub fn new(values: &HashMap<String, String>) -> Self {
+        // systemd reports unset counters as `[not set]` or as the maximum value
+        let counter = |property: &str| {
+            values
+                .get(property)
+                .and_then(|value| value.parse::<u64>().ok())
+                .filter(|value| *value != u64::MAX)
+        };
+        Sample {
+            time: glib::monotonic_time() as f64 / 1_000_000.0,
+            memory: counter("MemoryCurrent"),
+            cpu_usage: counter("CPUUsageNSec"),
+            io_read: counter("IOReadBytes"),
+            io_write: counter("IOWriteBytes"),
+            tasks: counter("TasksCurrent"),
+        }
+    }
+}
+
+/// The samples of a unit within the last `HISTORY_SPAN` seconds, oldest first.
+#[derive(Default)]
+pub struct History {
+    samples: VecDeque<Sample>,
+}
+
+impl History {
+    pub fn push(&mut self, sample: Sample) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|oldest| sample.time - oldest.time > HISTORY_SPAN)
+        {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// The points of each line of the graph as time and value, rates are calculated between consecutive samples.
+    pub fn series(&self, graph: Graph) -> Vec<Vec<(f64, f64)>> {
+        let gauge = |counter: fn(&Sample) -> Option<u64>| {
+            self.samples
+                .iter()
+                .filter_map(|sample| Some((sample.time, counter(sample)? as f64)))
+                .collect::<Vec<_>>()
+        };
+        let rate = |counter: fn(&Sample) -> Option<u64>, factor: f64| {
+            self.samples
+                .iter()
+                .zip(self.samples.iter().skip(1))
+                .filter_map(|(previous, sample)| {
+                    let elapsed = sample.time - previous.time;
+                    let delta = counter(sample)?.checked_sub(counter(previous)?)?;
+                    (elapsed > 0.0).then(|| (sample.time, delta as f64 / elapsed * factor))
+                })
+                .collect::<Vec<_>>()
+        };
+        match graph {
+            Graph::Memory => vec![gauge(|sample| sample.memory)],
+            // nanoseconds per second to percent of one CPU
+            Graph::Cpu => vec![rate(|sample| sample.cpu_usage, 100.0 / 1_000_000_000.0)],
+            Graph::Io => vec![
+                rate(|sample| sample.io_read, 1.0),
+                rate(|sample| sample.io_write, 1.0),
+            ],
+            Graph::Tasks => vec![gauge(|sample| sample.tasks)],
+        }
+    }
+}
+
+/// The state of a watched unit, compared between samples to notify about changes.
+#[derive(Clone)]
+pub struct Status {
+    pub active_state: String,
+    /// Number of automatic restarts, see `Restart=` in `man systemd.service`.
+    pub restarts: u32,
+}
+
+impl Status {
+    /// Creates the status from the values of `STATUS_PROPERTIES`.
+    pub fn new(values: &HashMap<String, String>) -> Self {
+        Status {
+            active_state: values.get("ActiveState").cloned().unwrap_or_default(),
+            restarts: values
+                .get("NRestarts")
+                .and_then(|restarts| restarts.parse().ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The change worth notifying about since the `previous` status, the most severe one if there are several.
+    pub fn change_since(&self, previous: &Status) -> Option<StatusChange> {
+        if self.active_state == "failed" && previous.active_state != "failed" {
+            Some(StatusChange::Failed)
+        } else if self.restarts > previous.restarts {
+            Some(StatusChange::Restarted(self.restarts))
+        } else if previous.active_state == "active" && self.active_state != "active" {
+            Some(StatusChange::Deactivated(self.active_state.clone()))
+        } else {
+            None
+        }
+    }
+}
+
+/// A change of a watched unit users are notified about.
+pub enum StatusChange {
+    /// The unit entered the `failed` state.
+    Failed,
+    /// The service was restarted automatically, with the number of restarts so far.
+    Restarted(u32),
+    /// The unit left the `active` state for the given state, e.g. `deactivating`.
+    Deactivated(String),
+}
+
+impl StatusChange {
+    pub fn title(&self, unit_name: &str) -> String {
+        match self {
+            StatusChange::Failed => format!("{unit_name} failed"),
+            StatusChange::Restarted(_) => format!("{unit_name} restarted"),
+            StatusChange::Deactivated(_) => format!("{unit_name} is no longer active"),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self {
+            StatusChange::Failed => String::from("The unit entered the failed state."),
+            StatusChange::Restarted(restarts) => {
+                format!("The service was restarted automatically, {restarts} times so far.")
+            }
+            StatusChange::Deactivated(state) => format!("The unit is {state} now."),
+        }
+    }
+}
+
+/// A graph in the resources view of the unit details.
+#[derive(Clone, Copy)]
+pub enum Graph {
+    Memory,
+    Cpu,
+    /// Read and write rate as two lines.
+    Io,
+    Tasks,
+}
+
+impl Graph {
+    pub const ALL: [Graph; 4] = [Graph::Memory, Graph::Cpu, Graph::Io, Graph::Tasks];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Graph::Memory => "Memory",
+            Graph::Cpu => "CPU",
+            Graph::Io => "IO Read / Write",
+            Graph::Tasks => "Tasks",
+        }
+    }
+
+    /// Formats a value of the graph, e.g. `12.3 MB` for memory.
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            Graph::Memory => glib::format_size(value as u64).to_string(),
+            Graph::Cpu => format!("{value:.1} %"),
+            Graph::Io => format!("{}/s", glib::format_size(value as u64)),
+            Graph::Tasks => format!("{value:.0}"),
+        }
+    }
+}
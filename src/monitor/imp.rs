@@ -0,0 +1,86 @@
+use crate::monitor::resource::{PreviousSample, ResourceObject};
+use adw::glib::subclass::InitializingObject;
+use adw::glib::SourceId;
+use adw::subclass::prelude::*;
+use adw::{gio, glib, StatusPage};
+use gtk::{ColumnView, CompositeTemplate, Label, SpinButton, Stack, ToggleButton};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
+use std::time::Instant;
+
+// Object holding the state
+#[derive(CompositeTemplate, Default)]
+#[template(resource = "/com/journeycorner/systemd-gtk/monitor.xml")]
+pub struct ResourceMonitor {
+    #[template_child]
+    pub summary_label: TemplateChild<Label>,
+
+    #[template_child]
+    pub interval_button: TemplateChild<SpinButton>,
+
+    #[template_child]
+    pub pause_button: TemplateChild<ToggleButton>,
+
+    #[template_child]
+    pub stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub column_view: TemplateChild<ColumnView>,
+
+    #[template_child]
+    pub error_page: TemplateChild<StatusPage>,
+
+    pub settings: OnceCell<gio::Settings>,
+
+    pub store: RefCell<Option<gio::ListStore>>,
+
+    /// Counters of each control group at the previous sample, by control group path.
+    pub previous_samples: RefCell<HashMap<String, PreviousSample>>,
+
+    pub sampled_at: Cell<Option<Instant>>,
+
+    /// Whether the counters are being read, so samples do not pile up.
+    pub sampling: Cell<bool>,
+
+    /// The periodic sampling, only running while the monitor is shown and not paused.
+    pub sample_source: RefCell<Option<SourceId>>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for ResourceMonitor {
+    // `NAME` needs to match `class` attribute of template
+    const NAME: &'static str = "ResourceMonitor";
+    type Type = super::ResourceMonitor;
+    type ParentType = adw::Bin;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+    }
+
+    fn instance_init(obj: &InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+// Trait shared by all GObjects
+impl ObjectImpl for ResourceMonitor {
+    fn constructed(&self) {
+        // Call "constructed" on parent
+        self.parent_constructed();
+
+        // Setup
+        self.store
+            .replace(Some(gio::ListStore::new::<ResourceObject>()));
+        let obj = self.obj();
+        obj.setup_settings();
+        obj.setup_column_view();
+        obj.setup_sampling();
+    }
+}
+
+// Trait shared by all widgets
+impl WidgetImpl for ResourceMonitor {}
+
+// Trait shared by all bins
+impl BinImpl for ResourceMonitor {}
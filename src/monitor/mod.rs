@@ -0,0 +1,321 @@
+mod imp;
+mod resource;
+
+use crate::settings;
+use crate::systemd::cgroup::{self, CgroupUsage};
+use crate::window;
+use adw::glib::{clone, ControlFlow};
+use adw::prelude::{Cast, CastNone, ListModelExt, SettingsExt, SettingsExtManual, ToVariant};
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use adw::{gio, glib};
+use gtk::pango::EllipsizeMode;
+use gtk::prelude::{ActionableExt, ListItemExt, ToggleButtonExt, WidgetExt};
+use gtk::{
+    Button, ColumnViewColumn, CustomSorter, Label, ListItem, NoSelection, SignalListItemFactory,
+    SortListModel, SortType,
+};
+use resource::{PreviousSample, ResourceObject};
+use std::cmp::Ordering;
+use std::time::Instant;
+
+/// Settings key of the seconds between two samples.
+const INTERVAL_KEY: &str = "monitor-interval";
+
+type Getter = fn(&ResourceObject) -> String;
+type Comparator = fn(&ResourceObject, &ResourceObject) -> Ordering;
+
+glib::wrapper! {
+    pub struct ResourceMonitor(ObjectSubclass<imp::ResourceMonitor>)
+        @extends adw::Bin, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ResourceMonitor {
+    fn setup_settings(&self) {
+        let settings = settings::app_settings();
+        settings
+            .bind(
+                INTERVAL_KEY,
+                &self.imp().interval_button.adjustment(),
+                "value",
+            )
+            .build();
+        self.imp()
+            .settings
+            .set(settings)
+            .expect("`settings` should not be set before calling `setup_settings`.");
+    }
+
+    fn settings(&self) -> &gio::Settings {
+        self.imp()
+            .settings
+            .get()
+            .expect("`settings` should be set in `setup_settings`.")
+    }
+
+    fn store(&self) -> gio::ListStore {
+        self.imp()
+            .store
+            .borrow()
+            .clone()
+            .expect("The store is set in `constructed`.")
+    }
+
+    fn setup_column_view(&self) {
+        let column_view = &self.imp().column_view;
+        let columns: [(&str, Getter, Comparator); 6] = [
+            ("UNIT", ResourceObject::unit_name, |a, b| {
+                a.unit_name().cmp(&b.unit_name())
+            }),
+            (
+                "CPU",
+                |resource| match resource.cpu() {
+                    cpu if cpu < 0.0 => String::new(),
+                    cpu => format!("{cpu:.1} %"),
+                },
+                |a, b| a.cpu().total_cmp(&b.cpu()),
+            ),
+            (
+                "MEMORY",
+                |resource| format_bytes(resource.memory()),
+                |a, b| a.memory().cmp(&b.memory()),
+            ),
+            (
+                "IO READ",
+                |resource| format_rate(resource.io_read()),
+                |a, b| a.io_read().total_cmp(&b.io_read()),
+            ),
+            (
+                "IO WRITE",
+                |resource| format_rate(resource.io_write()),
+                |a, b| a.io_write().total_cmp(&b.io_write()),
+            ),
+            (
+                "TASKS",
+                |resource| match resource.tasks() {
+                    tasks if tasks < 0 => String::new(),
+                    tasks => tasks.to_string(),
+                },
+                |a, b| a.tasks().cmp(&b.tasks()),
+            ),
+        ];
+        for (title, getter, comparator) in columns {
+            let column = ColumnViewColumn::new(Some(title), Some(label_factory(getter)));
+            column.set_resizable(true);
+            column.set_expand(title == "UNIT");
+            column.set_sorter(Some(&CustomSorter::new(move |a, b| {
+                let (Some(a), Some(b)) = (
+                    a.downcast_ref::<ResourceObject>(),
+                    b.downcast_ref::<ResourceObject>(),
+                ) else {
+                    return gtk::Ordering::Equal;
+                };
+                comparator(a, b).into()
+            })));
+            column_view.append_column(&column);
+        }
+        let jump_column = ColumnViewColumn::new(None, Some(jump_factory()));
+        column_view.append_column(&jump_column);
+
+        let sort_model = SortListModel::new(Some(self.store()), column_view.sorter());
+        column_view.set_model(Some(&NoSelection::new(Some(sort_model))));
+        let cpu_column = column_view
+            .columns()
+            .item(1)
+            .and_downcast::<ColumnViewColumn>();
+        column_view.sort_by_column(cpu_column.as_ref(), SortType::Descending);
+
+        // activating a row shows its unit in the unit table
+        column_view.connect_activate(|column_view, position| {
+            let resource = column_view
+                .model()
+                .and_then(|model| model.item(position))
+                .and_downcast::<ResourceObject>();
+            if let Some(resource) = resource {
+                let unit_name = resource.unit_name();
+                if let Err(error) =
+                    column_view.activate_action("win.unit-reveal", Some(&unit_name.to_variant()))
+                {
+                    window::toast(column_view, &format!("Could not show {unit_name}: {error}"));
+                }
+            }
+        });
+    }
+
+    /// Samples periodically while the monitor is mapped, i.e. its page is visible, and not paused.
+    fn setup_sampling(&self) {
+        self.connect_map(|monitor| monitor.restart_sampling());
+        self.connect_unmap(|monitor| monitor.stop_sampling());
+        self.imp().pause_button.connect_toggled(clone!(
+            #[weak(rename_to = monitor)]
+            self,
+            move |_| monitor.restart_sampling()
+        ));
+        self.settings().connect_changed(
+            Some(INTERVAL_KEY),
+            clone!(
+                #[weak(rename_to = monitor)]
+                self,
+                move |_, _| monitor.restart_sampling()
+            ),
+        );
+    }
+
+    fn restart_sampling(&self) {
+        self.stop_sampling();
+        if !self.is_mapped() || self.imp().pause_button.is_active() {
+            return;
+        }
+        self.sample();
+        let interval = self.settings().get::<u32>(INTERVAL_KEY);
+        let source = glib::timeout_add_seconds_local(
+            interval,
+            clone!(
+                #[weak(rename_to = monitor)]
+                self,
+                #[upgrade_or]
+                ControlFlow::Break,
+                move || {
+                    monitor.sample();
+                    ControlFlow::Continue
+                }
+            ),
+        );
+        self.imp().sample_source.replace(Some(source));
+    }
+
+    fn stop_sampling(&self) {
+        if let Some(source) = self.imp().sample_source.take() {
+            source.remove();
+        }
+    }
+
+    fn sample(&self) {
+        let imp = self.imp();
+        if imp.sampling.replace(true) {
+            return;
+        }
+        if imp.sampled_at.get().is_none() {
+            imp.stack.set_visible_child_name("loading");
+        }
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = monitor)]
+            self,
+            async move {
+                let usage = gio::spawn_blocking(cgroup::usage)
+                    .await
+                    .expect("The lookup needs to finish.");
+                let imp = monitor.imp();
+                imp.sampling.set(false);
+                match usage {
+                    Ok(usage) => monitor.update(&usage),
+                    Err(error) => {
+                        imp.error_page.set_description(Some(&error.to_string()));
+                        imp.stack.set_visible_child_name("error");
+                    }
+                }
+            }
+        ));
+    }
+
+    /// Replaces the shown usage, calculating rates since the previous sample.
+    fn update(&self, usage: &[CgroupUsage]) {
+        let imp = self.imp();
+        let now = Instant::now();
+        let elapsed = imp
+            .sampled_at
+            .replace(Some(now))
+            .map(|sampled_at| now.duration_since(sampled_at).as_secs_f64());
+        let previous_samples = imp.previous_samples.replace(
+            usage
+                .iter()
+                .map(|usage| (usage.path.clone(), PreviousSample::from(usage)))
+                .collect(),
+        );
+        let resources = usage
+            .iter()
+            .map(|usage| {
+                let previous = previous_samples.get(&usage.path).copied().zip(elapsed);
+                ResourceObject::new(usage, previous)
+            })
+            .collect::<Vec<_>>();
+        let store = self.store();
+        store.splice(0, store.n_items(), &resources);
+        imp.summary_label
+            .set_label(&format!("{} control groups", resources.len()));
+        imp.stack.set_visible_child_name("content");
+    }
+}
+
+fn format_bytes(bytes: i64) -> String {
+    if bytes < 0 {
+        String::new()
+    } else {
+        glib::format_size(bytes as u64).to_string()
+    }
+}
+
+fn format_rate(bytes_per_second: f64) -> String {
+    if bytes_per_second < 0.0 {
+        String::new()
+    } else {
+        format!("{}/s", glib::format_size(bytes_per_second as u64))
+    }
+}
+
+fn label_factory(getter: Getter) -> SignalListItemFactory {
+    let factory = SignalListItemFactory::new();
+    factory.connect_setup(|_, list_item| {
+        let label = Label::new(None);
+        label.set_xalign(0.0);
+        label.set_ellipsize(EllipsizeMode::Middle);
+        list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem")
+            .set_child(Some(&label));
+    });
+    factory.connect_bind(move |_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem");
+        let label = list_item
+            .child()
+            .and_downcast::<Label>()
+            .expect("The child has to be a `Label`.");
+        let resource = list_item.item().and_downcast::<ResourceObject>();
+        label.set_label(&resource.as_ref().map(getter).unwrap_or_default());
+        label.set_tooltip_text(resource.map(|resource| resource.path()).as_deref());
+    });
+    factory
+}
+
+/// A button per row showing the unit in the unit table via `win.unit-reveal`.
+fn jump_factory() -> SignalListItemFactory {
+    let factory = SignalListItemFactory::new();
+    factory.connect_setup(|_, list_item| {
+        let button = Button::from_icon_name("go-jump-symbolic");
+        button.set_tooltip_text(Some("Show in Unit Table"));
+        button.add_css_class("flat");
+        button.set_action_name(Some("win.unit-reveal"));
+        list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem")
+            .set_child(Some(&button));
+    });
+    factory.connect_bind(|_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem");
+        let button = list_item
+            .child()
+            .and_downcast::<Button>()
+            .expect("The child has to be a `Button`.");
+        let unit_name = list_item
+            .item()
+            .and_downcast::<ResourceObject>()
+            .map(|resource| resource.unit_name())
+            .unwrap_or_default();
+        button.set_action_target_value(Some(&unit_name.to_variant()));
+    });
+    factory
+}
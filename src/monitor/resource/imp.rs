@@ -0,0 +1,48 @@
+use adw::glib;
+use adw::glib::Properties;
+use adw::prelude::ObjectExt;
+use adw::subclass::prelude::*;
+use std::cell::RefCell;
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::ResourceObject)]
+pub struct ResourceObject {
+    #[property(get, construct_only)]
+    unit_name: RefCell<String>,
+
+    /// The control group path, e.g. `/system.slice/sshd.service`.
+    #[property(get, construct_only)]
+    path: RefCell<String>,
+
+    /// CPU usage since the previous sample in percent of one CPU, negative before the first sample.
+    #[property(get, construct_only)]
+    cpu: RefCell<f64>,
+
+    /// Memory in bytes, negative if memory accounting is off.
+    #[property(get, construct_only)]
+    memory: RefCell<i64>,
+
+    /// Bytes read per second since the previous sample, negative if unknown.
+    #[property(get, construct_only)]
+    io_read: RefCell<f64>,
+
+    /// Bytes written per second since the previous sample, negative if unknown.
+    #[property(get, construct_only)]
+    io_write: RefCell<f64>,
+
+    /// Number of tasks, negative if task accounting is off.
+    #[property(get, construct_only)]
+    tasks: RefCell<i64>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for ResourceObject {
+    const NAME: &'static str = "ResourceObject";
+    type Type = super::ResourceObject;
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for ResourceObject {}
@@ -0,0 +1,62 @@
+mod imp;
+
+use crate::systemd::cgroup::CgroupUsage;
+use adw::glib;
+use adw::glib::Object;
+
+glib::wrapper! {
+    pub struct ResourceObject(ObjectSubclass<imp::ResourceObject>);
+}
+
+/// Counters of the previous sample needed to calculate rates.
+#[derive(Clone, Copy)]
+pub struct PreviousSample {
+    pub cpu_usage: std::time::Duration,
+    pub io_read: Option<u64>,
+    pub io_write: Option<u64>,
+}
+
+impl From<&CgroupUsage> for PreviousSample {
+    fn from(usage: &CgroupUsage) -> Self {
+        PreviousSample {
+            cpu_usage: usage.cpu_usage,
+            io_read: usage.io_read,
+            io_write: usage.io_write,
+        }
+    }
+}
+
+impl ResourceObject {
+    /// Creates the row of a control group, rates are calculated from the previous sample `elapsed` seconds ago.
+    pub fn new(usage: &CgroupUsage, previous: Option<(PreviousSample, f64)>) -> Self {
+        let rate =
+            |current: Option<u64>, previous: Option<u64>, elapsed: f64| match (current, previous) {
+                (Some(current), Some(previous)) => {
+                    current.saturating_sub(previous) as f64 / elapsed
+                }
+                _ => -1.0,
+            };
+        let (cpu, io_read, io_write) = match previous {
+            Some((previous, elapsed)) if elapsed > 0.0 => (
+                usage
+                    .cpu_usage
+                    .saturating_sub(previous.cpu_usage)
+                    .as_secs_f64()
+                    / elapsed
+                    * 100.0,
+                rate(usage.io_read, previous.io_read, elapsed),
+                rate(usage.io_write, previous.io_write, elapsed),
+            ),
+            _ => (-1.0, -1.0, -1.0),
+        };
+        Object::builder()
+            .property("unit_name", &usage.unit_name)
+            .property("path", &usage.path)
+            .property("cpu", cpu)
+            .property("memory", usage.memory.map_or(-1, |memory| memory as i64))
+            .property("io_read", io_read)
+            .property("io_write", io_write)
+            .property("tasks", usage.tasks.map_or(-1, |tasks| tasks as i64))
+            .build()
+    }
+}
@@ -0,0 +1,77 @@
+use crate::process::object::ProcessObject;
+use adw::glib::subclass::InitializingObject;
+use adw::glib::SourceId;
+use adw::subclass::prelude::*;
+use adw::{gio, glib, StatusPage};
+use gtk::{ColumnView, CompositeTemplate, Stack};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Object holding the state
+#[derive(CompositeTemplate, Default)]
+#[template(resource = "/com/journeycorner/systemd-gtk/processes.xml")]
+pub struct ProcessList {
+    #[template_child]
+    pub stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub column_view: TemplateChild<ColumnView>,
+
+    #[template_child]
+    pub status_page: TemplateChild<StatusPage>,
+
+    /// Name of the unit whose processes are shown.
+    pub unit_name: RefCell<String>,
+
+    pub store: RefCell<Option<gio::ListStore>>,
+
+    /// CPU time of each process at the previous sample, to calculate the CPU usage in between.
+    pub cpu_times: RefCell<HashMap<u32, Duration>>,
+
+    pub sampled_at: Cell<Option<Instant>>,
+
+    /// Whether the processes are being read, so refreshes do not pile up.
+    pub refreshing: Cell<bool>,
+
+    /// The periodic refresh, only running while the list is shown.
+    pub refresh_source: RefCell<Option<SourceId>>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for ProcessList {
+    // `NAME` needs to match `class` attribute of template
+    const NAME: &'static str = "ProcessList";
+    type Type = super::ProcessList;
+    type ParentType = adw::Bin;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+    }
+
+    fn instance_init(obj: &InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+// Trait shared by all GObjects
+impl ObjectImpl for ProcessList {
+    fn constructed(&self) {
+        // Call "constructed" on parent
+        self.parent_constructed();
+
+        // Setup
+        self.store
+            .replace(Some(gio::ListStore::new::<ProcessObject>()));
+        let obj = self.obj();
+        obj.setup_column_view();
+        obj.setup_refresh();
+    }
+}
+
+// Trait shared by all widgets
+impl WidgetImpl for ProcessList {}
+
+// Trait shared by all bins
+impl BinImpl for ProcessList {}
@@ -0,0 +1,384 @@
+mod imp;
+
+use crate::process::object::ProcessObject;
+use crate::process::{self, Process, Signal};
+use crate::systemd::{self, cgroup};
+use crate::window;
+use adw::glib::{clone, ControlFlow};
+use adw::prelude::{
+    ActionMapExt, AlertDialogExt, AlertDialogExtManual, Cast, CastNone, FromVariant, ListModelExt,
+    ListModelExtManual, StaticVariantType, ToVariant,
+};
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use adw::{gio, glib, AlertDialog, ResponseAppearance};
+use gtk::pango::EllipsizeMode;
+use gtk::prelude::{GObjectPropertyExpressionExt, ListItemExt, SorterExt, WidgetExt};
+use gtk::{
+    ClosureExpression, ColumnViewColumn, CustomSorter, Label, ListItem, MenuButton, NoSelection,
+    SignalListItemFactory, SortListModel, SortType, SorterChange, Widget,
+};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Instant;
+use strum::IntoEnumIterator;
+
+/// Seconds between two refreshes of the processes while they are shown.
+const REFRESH_INTERVAL: u32 = 2;
+
+type Getter = fn(&ProcessObject) -> String;
+type Comparator = fn(&ProcessObject, &ProcessObject) -> Ordering;
+
+glib::wrapper! {
+    pub struct ProcessList(ObjectSubclass<imp::ProcessList>)
+        @extends adw::Bin, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ProcessList {
+    /// Shows the processes in the control group of the unit, refreshed periodically while the list is shown.
+    pub fn show_unit(&self, unit_name: &str) {
+        let imp = self.imp();
+        imp.unit_name.replace(unit_name.to_string());
+        imp.cpu_times.borrow_mut().clear();
+        imp.sampled_at.set(None);
+        self.store().remove_all();
+        imp.stack.set_visible_child_name("loading");
+        self.refresh();
+    }
+
+    fn store(&self) -> gio::ListStore {
+        self.imp()
+            .store
+            .borrow()
+            .clone()
+            .expect("The store is set in `constructed`.")
+    }
+
+    fn setup_column_view(&self) {
+        let column_view = &self.imp().column_view;
+        let columns: [(&str, Getter, Comparator); 6] = [
+            (
+                "PID",
+                |process| process.pid().to_string(),
+                |a, b| a.pid().cmp(&b.pid()),
+            ),
+            (
+                "PPID",
+                |process| process.ppid().to_string(),
+                |a, b| a.ppid().cmp(&b.ppid()),
+            ),
+            ("USER", ProcessObject::user, |a, b| a.user().cmp(&b.user())),
+            (
+                "CPU",
+                |process| match process.cpu() {
+                    cpu if cpu < 0.0 => String::new(),
+                    cpu => format!("{cpu:.1} %"),
+                },
+                |a, b| a.cpu().total_cmp(&b.cpu()),
+            ),
+            (
+                "RSS",
+                |process| glib::format_size(process.rss()).to_string(),
+                |a, b| a.rss().cmp(&b.rss()),
+            ),
+            ("COMMAND LINE", ProcessObject::command_line, |a, b| {
+                a.command_line().cmp(&b.command_line())
+            }),
+        ];
+        for (title, getter, comparator) in columns {
+            let column = ColumnViewColumn::new(Some(title), Some(label_factory(getter)));
+            column.set_resizable(true);
+            column.set_expand(title == "COMMAND LINE");
+            column.set_sorter(Some(&CustomSorter::new(move |a, b| {
+                let (Some(a), Some(b)) = (
+                    a.downcast_ref::<ProcessObject>(),
+                    b.downcast_ref::<ProcessObject>(),
+                ) else {
+                    return gtk::Ordering::Equal;
+                };
+                comparator(a, b).into()
+            })));
+            column_view.append_column(&column);
+        }
+        let signal_column = ColumnViewColumn::new(None, Some(self.signal_factory()));
+        column_view.append_column(&signal_column);
+
+        let sort_model = SortListModel::new(Some(self.store()), column_view.sorter());
+        column_view.set_model(Some(&NoSelection::new(Some(sort_model))));
+        let cpu_column = column_view
+            .columns()
+            .item(3)
+            .and_downcast::<ColumnViewColumn>();
+        column_view.sort_by_column(cpu_column.as_ref(), SortType::Descending);
+    }
+
+    /// Refreshes the processes periodically while the list is mapped, i.e. its view is visible.
+    fn setup_refresh(&self) {
+        self.connect_map(|list| {
+            list.refresh();
+            let source = glib::timeout_add_seconds_local(
+                REFRESH_INTERVAL,
+                clone!(
+                    #[weak]
+                    list,
+                    #[upgrade_or]
+                    ControlFlow::Break,
+                    move || {
+                        list.refresh();
+                        ControlFlow::Continue
+                    }
+                ),
+            );
+            if let Some(previous) = list.imp().refresh_source.replace(Some(source)) {
+                previous.remove();
+            }
+        });
+        self.connect_unmap(|list| {
+            if let Some(source) = list.imp().refresh_source.take() {
+                source.remove();
+            }
+        });
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let unit_name = imp.unit_name.borrow().clone();
+        if unit_name.is_empty() || imp.refreshing.replace(true) {
+            return;
+        }
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = list)]
+            self,
+            async move {
+                let lookup_unit_name = unit_name.clone();
+                let processes = gio::spawn_blocking(move || {
+                    let control_group = systemd::show(&lookup_unit_name, "ControlGroup")?;
+                    if control_group.is_empty() {
+                        return Ok(Vec::new());
+                    }
+                    cgroup::processes(&control_group)
+                })
+                .await
+                .expect("The lookup needs to finish.");
+                let imp = list.imp();
+                imp.refreshing.set(false);
+                if *imp.unit_name.borrow() != unit_name {
+                    return;
+                }
+                match processes {
+                    Ok(processes) if !processes.is_empty() => list.update(&processes),
+                    Ok(_) => list.show_empty(&format!("{unit_name} has no running processes")),
+                    Err(error) => {
+                        list.show_empty(&format!("Could not read the processes: {error}"))
+                    }
+                }
+            }
+        ));
+    }
+
+    /// A menu button per process offering the signals.
+    ///
+    /// The menu is built once per row, its `row.signal('TERM')` items send the signal to the process the row shows
+    /// when activated.
+    fn signal_factory(&self) -> SignalListItemFactory {
+        let factory = SignalListItemFactory::new();
+        factory.connect_setup(clone!(
+            #[weak(rename_to = list)]
+            self,
+            move |_, list_item| {
+                let list_item = list_item
+                    .downcast_ref::<ListItem>()
+                    .expect("Needs to be ListItem");
+                let menu = gio::Menu::new();
+                for signal in Signal::iter() {
+                    let item = gio::MenuItem::new(
+                        Some(&format!("SIG{signal} · {}", signal.description())),
+                        None,
+                    );
+                    item.set_action_and_target_value(
+                        Some("row.signal"),
+                        Some(&signal.to_string().to_variant()),
+                    );
+                    menu.append_item(&item);
+                }
+                let menu_button = MenuButton::new();
+                menu_button.set_icon_name("view-more-symbolic");
+                menu_button.set_tooltip_text(Some("Send Signal"));
+                menu_button.add_css_class("flat");
+                menu_button.set_menu_model(Some(&menu));
+
+                let signal_action =
+                    gio::SimpleAction::new("signal", Some(&String::static_variant_type()));
+                signal_action.connect_activate(clone!(
+                    #[weak]
+                    list,
+                    #[weak]
+                    list_item,
+                    move |_, parameter| {
+                        let Some(process) = list_item.item().and_downcast::<ProcessObject>() else {
+                            return;
+                        };
+                        let signal = parameter
+                            .and_then(String::from_variant)
+                            .and_then(|signal| Signal::from_str(&signal).ok())
+                            .expect("The action parameter needs to be a signal.");
+                        list.confirm_signal(process.pid(), signal);
+                    }
+                ));
+                let row_actions = gio::SimpleActionGroup::new();
+                row_actions.add_action(&signal_action);
+                menu_button.insert_action_group("row", Some(&row_actions));
+                list_item.set_child(Some(&menu_button));
+            }
+        ));
+        factory
+    }
+
+    /// Updates the shown processes in place, calculating the CPU usage since the previous sample.
+    ///
+    /// Rows of processes still running are kept, so an open signal menu stays open.
+    fn update(&self, processes: &[Process]) {
+        let imp = self.imp();
+        let now = Instant::now();
+        let elapsed = imp
+            .sampled_at
+            .replace(Some(now))
+            .map(|sampled_at| now.duration_since(sampled_at).as_secs_f64());
+        let previous_cpu_times = imp.cpu_times.replace(
+            processes
+                .iter()
+                .map(|process| (process.pid, process.cpu_time))
+                .collect::<HashMap<_, _>>(),
+        );
+        let mut samples = processes
+            .iter()
+            .map(|process| {
+                let cpu = match (elapsed, previous_cpu_times.get(&process.pid)) {
+                    (Some(elapsed), Some(previous)) if elapsed > 0.0 => {
+                        process.cpu_time.saturating_sub(*previous).as_secs_f64() / elapsed * 100.0
+                    }
+                    _ => -1.0,
+                };
+                (process.pid, (process, cpu))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let store = self.store();
+        for position in (0..store.n_items()).rev() {
+            let Some(process_object) = store.item(position).and_downcast::<ProcessObject>() else {
+                continue;
+            };
+            match samples.remove(&process_object.pid()) {
+                Some((process, cpu)) => process_object.update(process, cpu),
+                None => store.remove(position),
+            }
+        }
+        let started = samples
+            .into_values()
+            .map(|(process, cpu)| ProcessObject::new(process, cpu))
+            .collect::<Vec<_>>();
+        store.extend_from_slice(&started);
+        // the sort model only sorts added rows by itself, not ones updated in place
+        if let Some(sorter) = imp.column_view.sorter() {
+            sorter.changed(SorterChange::Different);
+        }
+        imp.stack.set_visible_child_name("content");
+    }
+
+    fn show_empty(&self, description: &str) {
+        let imp = self.imp();
+        self.store().remove_all();
+        imp.status_page.set_description(Some(description));
+        imp.stack.set_visible_child_name("empty");
+    }
+
+    /// Asks for confirmation before sending the signal, showing the command line of the process.
+    fn confirm_signal(&self, pid: u32, signal: Signal) {
+        let command_line = self
+            .store()
+            .iter::<ProcessObject>()
+            .flatten()
+            .find(|process| process.pid() == pid)
+            .map(|process| process.command_line())
+            .unwrap_or_default();
+        let dialog = AlertDialog::new(
+            Some(&format!("Send SIG{signal} to {pid}?")),
+            Some(&format!("{}.\n\n{command_line}", signal.description())),
+        );
+        dialog.add_responses(&[("cancel", "Cancel"), ("confirm", "Send")]);
+        dialog.set_response_appearance("confirm", ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = list)]
+            self,
+            async move {
+                if dialog.choose_future(&list).await != "confirm" {
+                    return;
+                }
+                let unit_name = list.imp().unit_name.borrow().clone();
+                let audited =
+                    gio::spawn_blocking(move || process::send_signal(&unit_name, pid, signal))
+                        .await
+                        .expect("The signal needs to be sent.");
+                if let Some(error) = audited.audit_error {
+                    window::toast(&list, &format!("Could not write the audit log: {error}"));
+                }
+                if let Err(error) = audited.result {
+                    let error_dialog = AlertDialog::new(
+                        Some(&format!("Could not send SIG{signal} to {pid}")),
+                        Some(&error),
+                    );
+                    error_dialog.add_response("close", "Close");
+                    error_dialog.choose_future(&list).await;
+                }
+                list.refresh();
+            }
+        ));
+    }
+}
+
+fn label_factory(getter: Getter) -> SignalListItemFactory {
+    let factory = SignalListItemFactory::new();
+    factory.connect_setup(move |_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("Needs to be ListItem");
+        let label = Label::new(None);
+        label.set_xalign(0.0);
+        label.set_ellipsize(EllipsizeMode::End);
+
+        // evaluated again when the row shows another process, or its process is updated in place
+        let item = list_item.property_expression_weak("item");
+        let text = ClosureExpression::with_callback(
+            [
+                item.clone().upcast(),
+                item.chain_property::<ProcessObject>("command_line")
+                    .upcast(),
+                item.chain_property::<ProcessObject>("cpu").upcast(),
+                item.chain_property::<ProcessObject>("rss").upcast(),
+            ],
+            move |values| {
+                // the first value is the `this` object of the expression, the parameters follow
+                values[1]
+                    .get::<Option<ProcessObject>>()
+                    .ok()
+                    .flatten()
+                    .map(|process| getter(&process))
+                    .unwrap_or_default()
+            },
+        );
+        text.bind(&label, "label", Widget::NONE);
+        text.chain_closure_with_callback(|values| {
+            values[1]
+                .get::<String>()
+                .ok()
+                .filter(|text| !text.is_empty())
+        })
+        .bind(&label, "tooltip-text", Widget::NONE);
+        list_item.set_child(Some(&label));
+    });
+    factory
+}
@@ -0,0 +1,256 @@
+pub mod list;
+pub mod object;
+
+use crate::audit::{AuditEntry, Audited};
+use crate::systemd::{self, JobResult};
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+use std::sync::LazyLock;
+use std::time::Duration;
+use strum::{Display, EnumIter, EnumString};
+
+/// Clock ticks per second the kernel reports process times in, see `clock_ticks`.
+static USER_HZ: LazyLock<u64> = LazyLock::new(|| clock_ticks().unwrap_or(DEFAULT_USER_HZ));
+
+/// `USER_HZ` on all common architectures, used if the auxiliary vector cannot be read.
+const DEFAULT_USER_HZ: u64 = 100;
+
+/// The `AT_CLKTCK` entry of the auxiliary vector, see `man getauxval`.
+const AT_CLKTCK: usize = 17;
+
+/// Signals offered for processes, see `man 7 signal`.
+#[derive(Debug, Display, EnumString, EnumIter, Clone, Copy, PartialEq)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum Signal {
+    Term,
+    Hup,
+    Int,
+    Quit,
+    Kill,
+    Stop,
+    Cont,
+    Usr1,
+    Usr2,
+}
+
+impl Signal {
+    /// What the signal usually does, for the signal menu.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Signal::Term => "Terminate",
+            Signal::Hup => "Hang up, often reloads the configuration",
+            Signal::Int => "Interrupt",
+            Signal::Quit => "Quit and dump core",
+            Signal::Kill => "Kill, cannot be handled",
+            Signal::Stop => "Pause",
+            Signal::Cont => "Continue if paused",
+            Signal::Usr1 => "User-defined signal 1",
+            Signal::Usr2 => "User-defined signal 2",
+        }
+    }
+}
+
+/// A process as read from `/proc`.
+#[derive(Clone)]
+pub struct Process {
+    pub pid: u32,
+    /// The parent process id, `0` for processes started by the kernel.
+    pub ppid: u32,
+    /// Name of the owning user, or e.g. `uid 1234` if it has no entry in `/etc/passwd`, like users from LDAP.
+    pub user: String,
+    /// The executable name, e.g. `sshd`.
+    pub name: String,
+    /// The arguments separated by spaces, or the name in brackets for kernel threads like `systemd-cgls` does.
+    pub command_line: String,
+    /// Resident set size in bytes.
+    pub rss: u64,
+    /// CPU time spent in user and kernel mode since the process started.
+    pub cpu_time: Duration,
+}
+
+impl Process {
+    /// Reads the process from `/proc`, `None` if it has already exited.
+    pub fn read(pid: u32, users: &HashMap<u32, String>) -> Option<Process> {
+        let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+        let name = status_field(&status, "Name")?.to_string();
+        let user = match status_uid(&status) {
+            Some(uid) => users
+                .get(&uid)
+                .cloned()
+                .unwrap_or_else(|| format!("uid {uid}")),
+            None => String::new(),
+        };
+        let command_line = fs::read(format!("/proc/{pid}/cmdline"))
+            .map(|cmdline| {
+                String::from_utf8_lossy(&cmdline)
+                    .split('\0')
+                    .filter(|arg| !arg.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_default();
+        let ppid = status_field(&status, "PPid")
+            .and_then(|ppid| ppid.parse().ok())
+            .unwrap_or_default();
+        // kernel threads have no resident set
+        let rss = status_field(&status, "VmRSS")
+            .and_then(|rss| rss.trim_end_matches("kB").trim().parse::<u64>().ok())
+            .map_or(0, |kilobytes| kilobytes * 1024);
+        let cpu_time = fs::read_to_string(format!("/proc/{pid}/stat"))
+            .ok()
+            .and_then(|stat| cpu_ticks(&stat))
+            .map_or(Duration::ZERO, |ticks| {
+                Duration::from_millis(ticks * 1000 / *USER_HZ)
+            });
+        let command_line = if command_line.is_empty() {
+            format!("[{name}]")
+        } else {
+            command_line
+        };
+        Some(Process {
+            pid,
+            ppid,
+            user,
+            name,
+            command_line,
+            rss,
+            cpu_time,
+        })
+    }
+}
+
+/// User names by uid from `/etc/passwd`, users of other sources like LDAP are missing.
+pub fn users() -> HashMap<u32, String> {
+    fs::read_to_string("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+/// Sends the signal to the process of the unit, asking for root permissions if the process belongs to another user.
+///
+/// The signal is only sent if the process is still part of the control group of the unit, as its PID may have been
+/// reused since it was listed. The signal is recorded in the audit log like any action on units, an error writing
+/// the log is returned along with the result.
+///
+/// # Related command
+/// The equivalent command is:
+/// ```
+/// kill -s [SIGNAL] [PID]
+/// ```
+///
+/// See `man kill` for more details.
+pub fn send_signal(unit_name: &str, pid: u32, signal: Signal) -> Audited<Result<(), String>> {
+    let result = belongs_to_unit(unit_name, pid).and_then(|()| {
+        // decided by the owner rather than the error of `kill`, whose wording depends on the locale
+        let own_uid = uid("self");
+        if own_uid != Some(0) && uid(&pid.to_string()) != own_uid {
+            kill(&["pkexec", "kill"], pid, signal)
+        } else {
+            kill(&["kill"], pid, signal)
+        }
+    });
+    let (outcome, error) = match &result {
+        Ok(()) => (JobResult::Done, ""),
+        Err(error) => (JobResult::Failed, error.as_str()),
+    };
+    let entry = AuditEntry::new(
+        unit_name,
+        &format!("kill -s {signal} {pid}"),
+        &outcome.to_string(),
+        error,
+    );
+    Audited::record(result, &[entry])
+}
+
+/// Checks that the process is in the control group of the unit or one of its child groups.
+fn belongs_to_unit(unit_name: &str, pid: u32) -> Result<(), String> {
+    let control_group =
+        systemd::show(unit_name, "ControlGroup").map_err(|error| error.to_string())?;
+    // e.g. `0::/system.slice/sshd.service` in the unified hierarchy
+    let process_group = fs::read_to_string(format!("/proc/{pid}/cgroup"))
+        .ok()
+        .and_then(|cgroups| {
+            cgroups
+                .lines()
+                .find_map(|line| line.strip_prefix("0::").map(str::to_string))
+        });
+    match process_group {
+        Some(process_group)
+            if !control_group.is_empty()
+                && (process_group == control_group
+                    || process_group.starts_with(&format!("{control_group}/"))) =>
+        {
+            Ok(())
+        }
+        _ => Err(format!("Process {pid} is no longer part of {unit_name}.")),
+    }
+}
+
+/// The real user id of the process, e.g. `uid("1234")`, or of this app with `uid("self")`.
+fn uid(pid: &str) -> Option<u32> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    status_uid(&status)
+}
+
+fn status_uid(status: &str) -> Option<u32> {
+    status_field(status, "Uid")
+        .and_then(|uids| uids.split_whitespace().next())
+        .and_then(|uid| uid.parse().ok())
+}
+
+fn kill(command: &[&str], pid: u32, signal: Signal) -> Result<(), String> {
+    let output = Command::new(command[0])
+        .args(&command[1..])
+        .args(["-s", &signal.to_string(), &pid.to_string()])
+        .output()
+        .map_err(|error| error.to_string())?;
+    match output.status.code() {
+        Some(0) => Ok(()),
+        // pkexec exits with 126 if the authentication dialog was dismissed and with 127 if it was not authorized
+        Some(126 | 127) if command[0] == "pkexec" => Err(String::from("Authorization cancelled.")),
+        _ => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
+
+/// Clock ticks per second from the auxiliary vector the kernel passed to this app, the value of `sysconf(_SC_CLK_TCK)`.
+fn clock_ticks() -> Option<u64> {
+    const WORD: usize = size_of::<usize>();
+    let auxv = fs::read("/proc/self/auxv").ok()?;
+    // pairs of native words, the type of the entry and its value
+    auxv.chunks_exact(2 * WORD)
+        .filter_map(|entry| {
+            let (key, value) = entry.split_at(WORD);
+            Some((
+                usize::from_ne_bytes(key.try_into().ok()?),
+                usize::from_ne_bytes(value.try_into().ok()?),
+            ))
+        })
+        .find(|(key, _)| *key == AT_CLKTCK)
+        .map(|(_, ticks)| ticks as u64)
+        .filter(|ticks| *ticks > 0)
+}
+
+/// User and system time from `/proc/PID/stat`, the fields after the parenthesized executable name.
+fn cpu_ticks(stat: &str) -> Option<u64> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let fields = fields.split_whitespace().collect::<Vec<_>>();
+    let utime = fields.get(11)?.parse::<u64>().ok()?;
+    let stime = fields.get(12)?.parse::<u64>().ok()?;
+    Some(utime + stime)
+}
+
+fn status_field<'a>(status: &'a str, field: &str) -> Option<&'a str> {
+    status.lines().find_map(|line| {
+        line.strip_prefix(field)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(str::trim)
+    })
+}
@@ -0,0 +1,41 @@
+use adw::glib;
+use adw::glib::Properties;
+use adw::prelude::ObjectExt;
+use adw::subclass::prelude::*;
+use std::cell::RefCell;
+
+// Object holding the state
+#[derive(Properties, Default)]
+#[properties(wrapper_type = super::ProcessObject)]
+pub struct ProcessObject {
+    #[property(get, construct_only)]
+    pid: RefCell<u32>,
+
+    #[property(get, construct_only)]
+    ppid: RefCell<u32>,
+
+    #[property(get, construct_only)]
+    user: RefCell<String>,
+
+    #[property(get, set)]
+    command_line: RefCell<String>,
+
+    /// CPU usage since the previous sample in percent of one CPU, negative before the first sample.
+    #[property(get, set)]
+    cpu: RefCell<f64>,
+
+    /// Resident set size in bytes.
+    #[property(get, set)]
+    rss: RefCell<u64>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for ProcessObject {
+    const NAME: &'static str = "ProcessObject";
+    type Type = super::ProcessObject;
+}
+
+// Trait shared by all GObjects
+#[glib::derived_properties]
+impl ObjectImpl for ProcessObject {}
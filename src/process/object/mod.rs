@@ -0,0 +1,30 @@
+mod imp;
+
+use crate::process::Process;
+use adw::glib;
+use adw::glib::Object;
+
+glib::wrapper! {
+    pub struct ProcessObject(ObjectSubclass<imp::ProcessObject>);
+}
+
+impl ProcessObject {
+    /// Creates the row of a process, `cpu` being its CPU usage in percent or negative if unknown yet.
+    pub fn new(process: &Process, cpu: f64) -> Self {
+        Object::builder()
+            .property("pid", process.pid)
+            .property("ppid", process.ppid)
+            .property("user", &process.user)
+            .property("command_line", &process.command_line)
+            .property("cpu", cpu)
+            .property("rss", process.rss)
+            .build()
+    }
+
+    /// Updates the row in place with a newer sample of the same process, so the shown widgets stay.
+    pub fn update(&self, process: &Process, cpu: f64) {
+        self.set_command_line(process.command_line.as_str());
+        self.set_cpu(cpu);
+        self.set_rss(process.rss);
+    }
+}
@@ -0,0 +1,60 @@
+/// Score of every matched character.
+const MATCH: i64 = 16;
+/// Bonus for a character matched right after the previous one.
+const CONSECUTIVE: i64 = 24;
+/// Bonus for a character matched at the start of the text or after a separator like `.`, `-` or `@`.
+const WORD_START: i64 = 32;
+/// Penalty for every skipped character between two matched ones.
+const GAP: i64 = 2;
+
+/// A fuzzy match with its score and the positions of the matched characters, counted in chars.
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+/// Matches the lowercase `pattern` as a subsequence of `text` ignoring case, so `ngxsvc` matches `nginx.service`.
+///
+/// Every occurrence of the first pattern character is tried as a start and the best scoring match is returned.
+/// The score is always positive.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = lowercase_chars(text);
+    let first = *pattern.first()?;
+    text.iter()
+        .enumerate()
+        .filter(|(_, c)| **c == first)
+        .filter_map(|(start, _)| match_from(&pattern, &text, start))
+        .max_by_key(|fuzzy_match| fuzzy_match.score)
+}
+
+/// Lowercases the text char by char, keeping the positions of the chars.
+pub fn lowercase_chars(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn match_from(pattern: &[char], text: &[char], start: usize) -> Option<FuzzyMatch> {
+    let mut positions: Vec<usize> = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut index = start;
+    for c in pattern {
+        let found = index + text[index..].iter().position(|t| t == c)?;
+        score += MATCH;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += WORD_START;
+        }
+        match positions.last() {
+            Some(last) if found == last + 1 => score += CONSECUTIVE,
+            Some(last) => score -= GAP * (found - last - 1) as i64,
+            None => score -= found as i64,
+        }
+        positions.push(found);
+        index = found + 1;
+    }
+    Some(FuzzyMatch {
+        score: score.max(1),
+        positions,
+    })
+}
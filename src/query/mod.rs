@@ -0,0 +1,413 @@
+mod fuzzy;
+
+use crate::systemd::unit::{unit_type, UnitObject};
+use adw::glib::{GString, Regex, RegexCompileFlags, RegexMatchFlags};
+use fuzzy::{fuzzy_match, lowercase_chars};
+
+/// A parsed search bar query, e.g. `type:service -state:inactive app-*@*.service /^user@\d+/`.
+///
+/// All terms have to match. Bare terms match the unit name or description, `key:value` terms a single property.
+/// Terms whose part before the first `:` is no known key are bare terms, e.g. device unit names.
+/// Values are matched as substring, as glob if they contain `*`, `?` or `[`, or as regex if enclosed in `/`.
+/// Unit names are matched fuzzily instead of as substring, so `ngxsvc` finds `nginx.service`.
+/// A leading `-` negates a term, negated names are matched as substring, double quotes allow values with spaces.
+#[derive(Default)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+struct Term {
+    negated: bool,
+    field: Field,
+    pattern: Pattern,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Field {
+    /// Unit name or description.
+    Any,
+    Name,
+    Description,
+    /// Unit type, the suffix of the unit name.
+    Type,
+    /// Active state.
+    State,
+    Sub,
+    Load,
+}
+
+enum Pattern {
+    Substring(String),
+    Glob(String),
+    Regex(Regex),
+}
+
+impl Query {
+    /// Parses the query, returning a message describing the first error.
+    pub fn parse(input: &str) -> Result<Query, String> {
+        let terms = tokenize(input)?
+            .iter()
+            .map(|token| Term::parse(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Query { terms })
+    }
+
+    pub fn matches(&self, unit_object: &UnitObject) -> bool {
+        self.terms.iter().all(|term| term.matches(unit_object))
+    }
+
+    /// Relevance of a matching unit, higher is better. Only fuzzy matches of the unit name count.
+    pub fn score(&self, unit_object: &UnitObject) -> i64 {
+        let unit_name = unit_object.unit_name();
+        self.terms
+            .iter()
+            .filter(|term| !term.negated && matches!(term.field, Field::Any | Field::Name))
+            .filter_map(|term| term.pattern.fuzzy_match(&unit_name))
+            .map(|fuzzy_match| fuzzy_match.score)
+            .sum()
+    }
+
+    /// Sorted char positions in the name or description `text` matched by the query, used for highlighting.
+    pub fn highlights(&self, field: Field, text: &str) -> Vec<usize> {
+        let mut positions = self
+            .terms
+            .iter()
+            .filter(|term| !term.negated && (term.field == field || term.field == Field::Any))
+            .flat_map(|term| match field {
+                Field::Name => term
+                    .pattern
+                    .fuzzy_match(text)
+                    .map(|fuzzy_match| fuzzy_match.positions)
+                    .unwrap_or_else(|| term.pattern.positions(text)),
+                _ => term.pattern.positions(text),
+            })
+            .collect::<Vec<_>>();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+}
+
+impl Term {
+    fn parse(token: &str) -> Result<Term, String> {
+        let (negated, token) = match token.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, token),
+        };
+        // a regex may contain colons, so it is never split into key and value
+        let (field, value) = match token.split_once(':') {
+            Some((key, value)) if !token.starts_with('/') => match Field::parse(key) {
+                Some(field) => (field, value),
+                None => (Field::Any, token),
+            },
+            _ => (Field::Any, token),
+        };
+        if value.is_empty() {
+            return Err(format!("Missing value in \"{token}\""));
+        }
+        let pattern = Pattern::parse(value, field)?;
+        Ok(Term {
+            negated,
+            field,
+            pattern,
+        })
+    }
+
+    fn matches(&self, unit_object: &UnitObject) -> bool {
+        let matches = match self.field {
+            Field::Any => {
+                self.matches_name(&unit_object.unit_name())
+                    || self.pattern.matches(&unit_object.description())
+            }
+            Field::Name => self.matches_name(&unit_object.unit_name()),
+            Field::Description => self.pattern.matches(&unit_object.description()),
+            Field::Type => {
+                let unit_name = unit_object.unit_name();
+                self.pattern.matches(unit_type(&unit_name))
+            }
+            Field::State => self.pattern.matches(&unit_object.state()),
+            Field::Sub => self.pattern.matches(&unit_object.sub_state()),
+            Field::Load => self.pattern.matches(&unit_object.load()),
+        };
+        matches != self.negated
+    }
+
+    /// Matches the unit name fuzzily, except for negated terms, which only exclude the names containing the value.
+    fn matches_name(&self, unit_name: &str) -> bool {
+        if self.negated {
+            self.pattern.matches(unit_name)
+        } else {
+            self.pattern.matches_name(unit_name)
+        }
+    }
+}
+
+impl Field {
+    /// The field of a `key:value` term, `None` if the key is unknown.
+    fn parse(key: &str) -> Option<Field> {
+        match key.to_lowercase().as_str() {
+            "name" | "unit" => Some(Field::Name),
+            "desc" | "description" => Some(Field::Description),
+            "type" => Some(Field::Type),
+            "state" | "active" => Some(Field::State),
+            "sub" => Some(Field::Sub),
+            "load" => Some(Field::Load),
+            _ => None,
+        }
+    }
+}
+
+impl Pattern {
+    fn parse(value: &str, field: Field) -> Result<Pattern, String> {
+        if let Some(regex) = value
+            .strip_prefix('/')
+            .and_then(|value| value.strip_suffix('/'))
+        {
+            return match Regex::new(regex, RegexCompileFlags::OPTIMIZE, RegexMatchFlags::DEFAULT) {
+                Ok(Some(regex)) => Ok(Pattern::Regex(regex)),
+                Ok(None) => Err(format!("Invalid regex /{regex}/")),
+                Err(error) => Err(format!("Invalid regex /{regex}/: {}", error.message())),
+            };
+        }
+        let value = value.to_lowercase();
+        if value.contains(['*', '?', '[']) {
+            Ok(Pattern::Glob(value))
+        } else if field == Field::Type {
+            // `type:service` must not match `type:service-watchdog`, types are matched exactly
+            Ok(Pattern::Glob(value))
+        } else {
+            Ok(Pattern::Substring(value))
+        }
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Pattern::Substring(substring) => value.to_lowercase().contains(substring.as_str()),
+            Pattern::Glob(glob) => glob_matches(glob, &value.to_lowercase()),
+            Pattern::Regex(regex) => regex
+                .match_(GString::from(value).as_gstr(), RegexMatchFlags::DEFAULT)
+                .is_some_and(|match_info| match_info.matches()),
+        }
+    }
+
+    /// Like `matches`, but substrings are matched fuzzily.
+    fn matches_name(&self, unit_name: &str) -> bool {
+        match self {
+            Pattern::Substring(_) => self.fuzzy_match(unit_name).is_some(),
+            _ => self.matches(unit_name),
+        }
+    }
+
+    fn fuzzy_match(&self, value: &str) -> Option<fuzzy::FuzzyMatch> {
+        match self {
+            Pattern::Substring(substring) => fuzzy_match(substring, value),
+            _ => None,
+        }
+    }
+
+    /// Char positions of the first match in the value, globs always match the whole value and are not highlighted.
+    fn positions(&self, value: &str) -> Vec<usize> {
+        match self {
+            Pattern::Substring(substring) => {
+                let needle = substring.chars().collect::<Vec<_>>();
+                lowercase_chars(value)
+                    .windows(needle.len())
+                    .position(|window| window == needle.as_slice())
+                    .map(|start| (start..start + needle.len()).collect())
+                    .unwrap_or_default()
+            }
+            Pattern::Glob(_) => vec![],
+            Pattern::Regex(regex) => regex
+                .match_(GString::from(value).as_gstr(), RegexMatchFlags::DEFAULT)
+                .filter(|match_info| match_info.matches())
+                .and_then(|match_info| match_info.fetch_pos(0))
+                .map(|(start, end)| {
+                    // convert the byte offsets to char positions
+                    value
+                        .char_indices()
+                        .enumerate()
+                        .filter(|(_, (offset, _))| (start as usize..end as usize).contains(offset))
+                        .map(|(position, _)| position)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Splits the input at whitespace, keeping double quoted parts together.
+fn tokenize(input: &str) -> Result<Vec<String>, String> {
+    let mut tokens = vec![];
+    let mut token = String::new();
+    let mut quoted = false;
+    for c in input.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("Missing closing quote"));
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Matches the whole value against a shell style glob with `*`, `?` and `[...]` character classes.
+///
+/// Backtracks only to the last `*`, which is enough as an earlier `*` could only match less, so the time is linear
+/// in the length of the glob times the length of the value.
+fn glob_matches(glob: &str, value: &str) -> bool {
+    let glob = glob.chars().collect::<Vec<_>>();
+    let value = value.chars().collect::<Vec<_>>();
+    let (mut glob_index, mut value_index) = (0, 0);
+    // the glob index after the last `*` and the value index the `*` matches up to
+    let mut last_star = None;
+    while value_index < value.len() {
+        if glob.get(glob_index) == Some(&'*') {
+            glob_index += 1;
+            last_star = Some((glob_index, value_index));
+            continue;
+        }
+        if let Some(length) = match_one(&glob[glob_index..], value[value_index]) {
+            glob_index += length;
+            value_index += 1;
+            continue;
+        }
+        // let the last `*` match one more char
+        let Some((star_glob_index, star_value_index)) = last_star else {
+            return false;
+        };
+        glob_index = star_glob_index;
+        value_index = star_value_index + 1;
+        last_star = Some((star_glob_index, value_index));
+    }
+    glob[glob_index..].iter().all(|c| *c == '*')
+}
+
+/// Matches the char against the start of the glob, returning the number of glob chars used, e.g. 5 for `[a-z]`.
+fn match_one(glob: &[char], c: char) -> Option<usize> {
+    match glob.first()? {
+        '*' => None,
+        '?' => Some(1),
+        // an unterminated class is matched literally
+        '[' => match glob.iter().position(|c| *c == ']') {
+            Some(end) => class_matches(&glob[1..end], c).then_some(end + 1),
+            None => (c == '[').then_some(1),
+        },
+        literal => (*literal == c).then_some(1),
+    }
+}
+
+/// Matches a character class like `a-z0-9`, negated by a leading `!` or `^`.
+fn class_matches(class: &[char], c: char) -> bool {
+    let (negated, class) = match class.first() {
+        Some('!') | Some('^') => (true, &class[1..]),
+        _ => (false, class),
+    };
+    let mut matches = false;
+    let mut index = 0;
+    while index < class.len() {
+        if index + 2 < class.len() && class[index + 1] == '-' {
+            matches |= (class[index]..=class[index + 2]).contains(&c);
+            index += 3;
+        } else {
+            matches |= class[index] == c;
+            index += 1;
+        }
+    }
+    matches != negated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_term(input: &str) -> Term {
+        let mut query = Query::parse(input).unwrap();
+        assert_eq!(query.terms.len(), 1);
+        query.terms.remove(0)
+    }
+
+    #[test]
+    fn parses_key_value_terms() {
+        let state = parse_term("-State:inactive");
+        assert!(state.negated);
+        assert_eq!(state.field, Field::State);
+        assert!(matches!(state.pattern, Pattern::Substring(ref value) if value == "inactive"));
+
+        let unit_type = parse_term("type:service");
+        assert_eq!(unit_type.field, Field::Type);
+        assert!(matches!(unit_type.pattern, Pattern::Glob(_)));
+    }
+
+    #[test]
+    fn keeps_unknown_keys_in_bare_terms() {
+        let device = parse_term("sys-devices-pci0000:00.device");
+        assert_eq!(device.field, Field::Any);
+        assert!(matches!(
+            device.pattern,
+            Pattern::Substring(ref value) if value == "sys-devices-pci0000:00.device"
+        ));
+        assert_eq!(parse_term("Note:").field, Field::Any);
+    }
+
+    #[test]
+    fn matches_negated_names_as_substrings() {
+        assert!(parse_term("ssh").matches_name("systemd-hostnamed.service"));
+        let negated = parse_term("-ssh");
+        assert!(!negated.matches_name("systemd-hostnamed.service"));
+        assert!(negated.matches_name("sshd.service"));
+        assert!(negated.matches_name("OpenSSH.service"));
+    }
+
+    #[test]
+    fn parses_quotes_globs_and_regexes() {
+        let query = Query::parse(r#"desc:"Network Manager" app-*@*.service /^user@\d+:/"#).unwrap();
+        assert_eq!(query.terms.len(), 3);
+        assert!(matches!(
+            query.terms[0].pattern,
+            Pattern::Substring(ref value) if value == "network manager"
+        ));
+        assert!(matches!(query.terms[1].pattern, Pattern::Glob(_)));
+        assert_eq!(query.terms[2].field, Field::Any);
+        assert!(matches!(query.terms[2].pattern, Pattern::Regex(_)));
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        assert!(Query::parse(r#"desc:"open"#).is_err());
+        assert!(Query::parse("state:").is_err());
+        assert!(Query::parse("/(/").is_err());
+        assert!(Query::parse("  ").unwrap().terms.is_empty());
+    }
+
+    #[test]
+    fn matches_globs() {
+        assert!(glob_matches("app-*@*.service", "app-firefox@1234.service"));
+        assert!(!glob_matches("app-*@*.service", "app-firefox.service"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("a?c", "abc"));
+        assert!(!glob_matches("a?c", "ac"));
+        assert!(glob_matches("tty[0-9]", "tty3"));
+        assert!(!glob_matches("tty[!0-9]", "tty3"));
+        assert!(glob_matches("tty[^a-z]*", "tty12"));
+        assert!(glob_matches("a[b", "a[b"));
+        assert!(!glob_matches("service", "service-watchdog"));
+        assert!(glob_matches("*.*.*", "a.b.c.d"));
+    }
+
+    #[test]
+    fn matches_globs_in_linear_time() {
+        let value = "a".repeat(1_000);
+        assert!(!glob_matches(&format!("{}b", "*a".repeat(50)), &value));
+        assert!(glob_matches(&"*a".repeat(50), &value));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::APP_ID;
+use adw::{gio, glib};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The settings schema compiled by the build script, for binaries run without installing the schema.
+const COMPILED_SCHEMAS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gschemas.compiled"));
+
+thread_local! {
+    static SETTINGS: gio::Settings = load();
+}
+
+/// The settings of the app, shared by all widgets.
+///
+/// If the schema is not installed, e.g. for a standalone release binary, the bundled schema is used and the settings
+/// are kept in a key file in the user config directory instead of aborting, as `gio::Settings::new` does.
+pub fn app_settings() -> gio::Settings {
+    SETTINGS.with(gio::Settings::clone)
+}
+
+fn load() -> gio::Settings {
+    let installed = gio::SettingsSchemaSource::default()
+        .and_then(|source| source.lookup(APP_ID, true))
+        .is_some();
+    if installed {
+        return gio::Settings::new(APP_ID);
+    }
+    // the temporary directory is the fallback if the runtime directory is not writable, e.g. without a session
+    let schema = [glib::user_runtime_dir(), std::env::temp_dir()]
+        .iter()
+        .find_map(|directory| {
+            bundled_schema(&directory.join(APP_ID))
+                .inspect_err(|error| {
+                    glib::g_warning!(
+                        APP_ID,
+                        "Could not read the bundled settings schema in {}: {error}",
+                        directory.display()
+                    );
+                })
+                .ok()
+        });
+    let Some(schema) = schema else {
+        glib::g_critical!(APP_ID, "No settings schema is available, exiting.");
+        std::process::exit(1);
+    };
+    gio::Settings::new_full(&schema, Some(&keyfile_backend()), None)
+}
+
+/// Writes the bundled schema to the directory, as schema sources can only be read from a directory.
+fn bundled_schema(directory: &Path) -> io::Result<gio::SettingsSchema> {
+    fs::create_dir_all(directory)?;
+    fs::write(directory.join("gschemas.compiled"), COMPILED_SCHEMAS)?;
+    gio::SettingsSchemaSource::from_directory(directory, None, false)
+        .map_err(io::Error::other)?
+        .lookup(APP_ID, false)
+        .ok_or_else(|| io::Error::other(format!("{APP_ID} is missing in the bundled schema")))
+}
+
+/// Keeps the settings in `settings.ini` in the user config directory, as dconf only knows installed schemas.
+fn keyfile_backend() -> gio::SettingsBackend {
+    let directory = glib::user_config_dir().join(APP_ID);
+    if let Err(error) = fs::create_dir_all(&directory) {
+        glib::g_warning!(
+            APP_ID,
+            "Could not create {}, settings will not be saved: {error}",
+            directory.display()
+        );
+    }
+    let file = directory.join("settings.ini");
+    gio::keyfile_settings_backend_new(&file.to_string_lossy(), "/", None)
+}
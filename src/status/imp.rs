@@ -0,0 +1,91 @@
+use adw::glib::subclass::InitializingObject;
+use adw::subclass::prelude::*;
+use adw::{glib, ActionRow, PreferencesGroup, StatusPage};
+use gtk::{CompositeTemplate, Image, Label, Stack};
+use std::cell::RefCell;
+
+// Object holding the state
+#[derive(CompositeTemplate, Default)]
+#[template(resource = "/com/journeycorner/systemd-gtk/status.xml")]
+pub struct StatusCard {
+    #[template_child]
+    pub stack: TemplateChild<Stack>,
+
+    #[template_child]
+    pub unit_group: TemplateChild<PreferencesGroup>,
+
+    #[template_child]
+    pub loaded_row: TemplateChild<ActionRow>,
+
+    #[template_child]
+    pub path_row: TemplateChild<ActionRow>,
+
+    #[template_child]
+    pub active_row: TemplateChild<ActionRow>,
+
+    #[template_child]
+    pub active_icon: TemplateChild<Image>,
+
+    #[template_child]
+    pub main_pid_row: TemplateChild<ActionRow>,
+
+    #[template_child]
+    pub tasks_row: TemplateChild<ActionRow>,
+
+    #[template_child]
+    pub memory_row: TemplateChild<ActionRow>,
+
+    #[template_child]
+    pub cpu_row: TemplateChild<ActionRow>,
+
+    #[template_child]
+    pub cgroup_group: TemplateChild<PreferencesGroup>,
+
+    #[template_child]
+    pub cgroup_label: TemplateChild<Label>,
+
+    #[template_child]
+    pub journal_label: TemplateChild<Label>,
+
+    #[template_child]
+    pub error_page: TemplateChild<StatusPage>,
+
+    /// Name of the unit whose status is shown.
+    pub unit_name: RefCell<String>,
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for StatusCard {
+    // `NAME` needs to match `class` attribute of template
+    const NAME: &'static str = "StatusCard";
+    type Type = super::StatusCard;
+    type ParentType = adw::Bin;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+    }
+
+    fn instance_init(obj: &InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+// Trait shared by all GObjects
+impl ObjectImpl for StatusCard {
+    fn constructed(&self) {
+        // Call "constructed" on parent
+        self.parent_constructed();
+
+        // Setup
+        let obj = self.obj();
+        obj.setup_links();
+        obj.setup_refresh();
+    }
+}
+
+// Trait shared by all widgets
+impl WidgetImpl for StatusCard {}
+
+// Trait shared by all bins
+impl BinImpl for StatusCard {}
@@ -0,0 +1,260 @@
+mod imp;
+
+use crate::systemd::cgroup::Cgroup;
+use crate::systemd::status::{self, UnitStatus};
+use crate::systemd::State;
+use crate::window;
+use adw::glib::clone;
+use adw::prelude::{ActionRowExt, PreferencesGroupExt, ToVariant};
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use adw::{gio, glib};
+use gtk::prelude::WidgetExt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+glib::wrapper! {
+    pub struct StatusCard(ObjectSubclass<imp::StatusCard>)
+        @extends adw::Bin, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl StatusCard {
+    /// Shows the status of the unit, read again whenever the card is shown.
+    pub fn show_unit(&self, unit_name: &str) {
+        let imp = self.imp();
+        imp.unit_name.replace(unit_name.to_string());
+        imp.stack.set_visible_child_name("loading");
+        self.refresh();
+    }
+
+    /// Shows the processes of the unit when one of the PIDs in the control group is clicked.
+    fn setup_links(&self) {
+        self.imp().cgroup_label.connect_activate_link(clone!(
+            #[weak(rename_to = card)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, _| {
+                if let Err(error) =
+                    card.activate_action("detail.view", Some(&"processes".to_variant()))
+                {
+                    window::toast(&card, &format!("Could not show the processes: {error}"));
+                }
+                glib::Propagation::Stop
+            }
+        ));
+    }
+
+    /// Reads the status again when the card is shown, as it changes while the unit runs.
+    fn setup_refresh(&self) {
+        self.connect_map(|card| card.refresh());
+    }
+
+    /// Reads the status of the unit again, keeping the shown status until it is read.
+    pub fn refresh(&self) {
+        let unit_name = self.imp().unit_name.borrow().clone();
+        if unit_name.is_empty() {
+            return;
+        }
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = card)]
+            self,
+            async move {
+                let lookup_unit_name = unit_name.clone();
+                let status = gio::spawn_blocking(move || status::unit_status(&lookup_unit_name))
+                    .await
+                    .expect("The lookup needs to finish.");
+                let imp = card.imp();
+                if *imp.unit_name.borrow() != unit_name {
+                    return;
+                }
+                match status {
+                    Ok(status) => {
+                        card.update(&status);
+                        imp.stack.set_visible_child_name("content");
+                    }
+                    Err(error) => {
+                        imp.error_page.set_description(Some(&error.to_string()));
+                        imp.stack.set_visible_child_name("error");
+                    }
+                }
+            }
+        ));
+    }
+
+    fn update(&self, status: &UnitStatus) {
+        let imp = self.imp();
+        imp.unit_group
+            .set_title(&glib::markup_escape_text(&status.description));
+
+        // e.g. `loaded; enabled; preset: enabled` like the `Loaded:` line of `systemctl status`
+        let mut loaded = vec![status.load_state.clone()];
+        if !status.unit_file_state.is_empty() {
+            loaded.push(status.unit_file_state.clone());
+        }
+        if !status.vendor_preset.is_empty() {
+            loaded.push(format!("preset: {}", status.vendor_preset));
+        }
+        imp.loaded_row.set_subtitle(&loaded.join("; "));
+
+        imp.path_row.set_visible(!status.fragment_path.is_empty());
+        imp.path_row
+            .set_subtitle(&glib::markup_escape_text(&status.fragment_path));
+
+        // e.g. `active (running) since Mon 2024-05-06 10:00:00 CEST; 2h 13min ago`
+        let mut active = format!("{} ({})", status.active_state, status.sub_state);
+        if let Some(since) = status.since {
+            let ago = SystemTime::now().duration_since(since).unwrap_or_default();
+            active.push_str(&format!(
+                " since {}; {} ago",
+                format_time(since),
+                format_duration(ago)
+            ));
+        }
+        imp.active_row.set_subtitle(&active);
+        let state = State::from_str(&status.active_state).ok();
+        imp.active_icon
+            .set_icon_name(state.map(|state| state.icon_name()));
+        let css_class = state.map(|state| state.css_class());
+        imp.active_icon
+            .set_css_classes(css_class.as_deref().as_slice());
+
+        imp.main_pid_row.set_visible(status.main_pid.is_some());
+        if let Some(main_pid) = status.main_pid {
+            let name = status
+                .cgroup
+                .as_ref()
+                .and_then(|cgroup| process_name(cgroup, main_pid));
+            imp.main_pid_row.set_subtitle(&match name {
+                Some(name) => format!("{main_pid} ({})", glib::markup_escape_text(&name)),
+                None => main_pid.to_string(),
+            });
+        }
+
+        imp.tasks_row.set_visible(status.tasks.is_some());
+        if let Some(tasks) = status.tasks {
+            imp.tasks_row.set_subtitle(&tasks.to_string());
+        }
+        imp.memory_row.set_visible(status.memory.is_some());
+        if let Some(memory) = status.memory {
+            imp.memory_row.set_subtitle(&glib::format_size(memory));
+        }
+        imp.cpu_row.set_visible(status.cpu_usage.is_some());
+        if let Some(cpu_usage) = status.cpu_usage {
+            imp.cpu_row.set_subtitle(&format_cpu_usage(cpu_usage));
+        }
+
+        let cgroup = status
+            .cgroup
+            .as_ref()
+            .filter(|cgroup| has_processes(cgroup));
+        imp.cgroup_group.set_visible(cgroup.is_some());
+        if let Some(cgroup) = cgroup {
+            imp.cgroup_group
+                .set_description(Some(&glib::markup_escape_text(&cgroup.path)));
+            let mut markup = String::new();
+            tree_markup(cgroup, "", &mut markup);
+            imp.cgroup_label.set_markup(markup.trim_end());
+        }
+
+        imp.journal_label.set_text(status.journal.trim_end());
+    }
+}
+
+/// Formats the time like `systemctl status` does, e.g. `Mon 2024-05-06 10:00:00 CEST`.
+fn format_time(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    glib::DateTime::from_unix_local(seconds as i64)
+        .and_then(|time| time.format("%a %Y-%m-%d %H:%M:%S %Z"))
+        .map(|time| time.to_string())
+        .unwrap_or_default()
+}
+
+/// Formats the duration with its two most significant units like systemd does, e.g. `2h 13min`.
+fn format_duration(duration: Duration) -> String {
+    let units = [(86_400, "d"), (3_600, "h"), (60, "min"), (1, "s")];
+    let parts = units
+        .iter()
+        .scan(duration.as_secs(), |rest, (size, unit)| {
+            let value = *rest / size;
+            *rest %= size;
+            Some((value, unit))
+        })
+        .skip_while(|(value, _)| *value == 0)
+        .take(2)
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        String::from("0s")
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Formats the CPU time with milliseconds below a minute like systemd does, e.g. `1.234s`.
+fn format_cpu_usage(cpu_usage: Duration) -> String {
+    if cpu_usage.as_secs() < 60 {
+        format!("{:.3}s", cpu_usage.as_secs_f64())
+    } else {
+        format_duration(cpu_usage)
+    }
+}
+
+/// Whether the control group or any of its child groups contains a process.
+fn has_processes(cgroup: &Cgroup) -> bool {
+    !cgroup.processes.is_empty() || cgroup.children.iter().any(has_processes)
+}
+
+fn process_name(cgroup: &Cgroup, pid: u32) -> Option<String> {
+    cgroup
+        .processes
+        .iter()
+        .find(|process| process.pid == pid)
+        .map(|process| process.name.clone())
+        .or_else(|| {
+            cgroup
+                .children
+                .iter()
+                .find_map(|child| process_name(child, pid))
+        })
+}
+
+/// Draws the processes and child groups as a tree like `systemctl status`, with the PIDs as links.
+fn tree_markup(cgroup: &Cgroup, prefix: &str, markup: &mut String) {
+    let children = cgroup
+        .children
+        .iter()
+        .filter(|child| has_processes(child))
+        .collect::<Vec<_>>();
+    let count = cgroup.processes.len() + children.len();
+    let branch = |index: usize| {
+        if index + 1 == count {
+            "└─"
+        } else {
+            "├─"
+        }
+    };
+
+    for (index, process) in cgroup.processes.iter().enumerate() {
+        markup.push_str(&format!(
+            "{prefix}{}<a href=\"{pid}\">{pid}</a> {}\n",
+            branch(index),
+            glib::markup_escape_text(&process.command_line),
+            pid = process.pid,
+        ));
+    }
+    for (index, child) in children.into_iter().enumerate() {
+        let index = cgroup.processes.len() + index;
+        markup.push_str(&format!(
+            "{prefix}{}{}\n",
+            branch(index),
+            glib::markup_escape_text(&child.name)
+        ));
+        let indent = if index + 1 == count { "  " } else { "│ " };
+        tree_markup(child, &format!("{prefix}{indent}"), markup);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::process::{self, Process};
+use crate::systemd::unit::unit_type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Mount point of the unified control group hierarchy.
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Unit types that own a control group.
+const CGROUP_UNIT_TYPES: [&str; 6] = ["slice", "scope", "service", "socket", "mount", "swap"];
+
+/// A control group with its processes and child groups.
+pub struct Cgroup {
+    /// The last path component, e.g. `sshd.service`, or `-.slice` for the root.
+    pub name: String,
+    /// The path below the hierarchy root, e.g. `/system.slice/sshd.service`.
+    pub path: String,
+    pub processes: Vec<Process>,
+    pub children: Vec<Cgroup>,
+}
+
+impl Cgroup {
+    /// The unit owning the control group, `None` for groups created by the processes themselves.
+    pub fn unit_name(&self) -> Option<String> {
+        CGROUP_UNIT_TYPES
+            .contains(&unit_type(&self.name))
+            .then(|| self.name.clone())
+    }
+}
+
+/// Reads the control group hierarchy with the processes inside each group.
+///
+/// # Returns
+/// - The root control group `-.slice`, child groups sorted by name.
+///
+/// # Errors
+/// - Returns an error if the unified control group hierarchy is not mounted at `/sys/fs/cgroup`.
+///
+/// # Related command
+/// The equivalent command is:
+/// ```
+/// systemd-cgls --all
+/// ```
+///
+/// See `man systemd-cgls` for more details.
+pub fn cgroup_tree() -> std::io::Result<Cgroup> {
+    let users = process::users();
+    read_cgroup(Path::new(CGROUP_ROOT), "/", &users)
+}
+
+/// Reads the control group with its processes and child groups.
+///
+/// # Parameters
+/// - `control_group`: The path below the hierarchy root, the `ControlGroup` property of a unit.
+///
+/// # Errors
+/// - Returns an error if the control group does not exist, e.g. because the unit is not running.
+pub fn cgroup(control_group: &str) -> std::io::Result<Cgroup> {
+    let users = process::users();
+    let directory = Path::new(CGROUP_ROOT).join(control_group.trim_start_matches('/'));
+    read_cgroup(&directory, control_group, &users)
+}
+
+/// Reads the processes in the control group and all of its child groups.
+///
+/// # Parameters
+/// - `control_group`: The path below the hierarchy root, the `ControlGroup` property of a unit.
+///
+/// # Errors
+/// - Returns an error if the control group does not exist, e.g. because the unit is not running.
+pub fn processes(control_group: &str) -> std::io::Result<Vec<Process>> {
+    let mut processes = Vec::new();
+    collect_processes(cgroup(control_group)?, &mut processes);
+    Ok(processes)
+}
+
+fn collect_processes(cgroup: Cgroup, processes: &mut Vec<Process>) {
+    processes.extend(cgroup.processes);
+    for child in cgroup.children {
+        collect_processes(child, processes);
+    }
+}
+
+fn read_cgroup(
+    directory: &Path,
+    path: &str,
+    users: &HashMap<u32, String>,
+) -> std::io::Result<Cgroup> {
+    let mut children = fs::read_dir(directory)?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let child_path = format!("{}/{name}", path.trim_end_matches('/'));
+            // groups may vanish while being read
+            read_cgroup(&entry.path(), &child_path, users).ok()
+        })
+        .collect::<Vec<_>>();
+    children.sort_by(|one, two| one.name.cmp(&two.name));
+
+    let processes = fs::read_to_string(directory.join("cgroup.procs"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|pid| pid.parse().ok())
+        .filter_map(|pid| Process::read(pid, users))
+        .collect();
+    let name = match path.rsplit_once('/') {
+        Some((_, name)) if !name.is_empty() => name.to_string(),
+        _ => String::from("-.slice"),
+    };
+    Ok(Cgroup {
+        name,
+        path: path.to_string(),
+        processes,
+        children,
+    })
+}
+
+/// Unit types listed in the resource monitor, the ones processes run in.
+const MONITORED_UNIT_TYPES: [&str; 3] = ["slice", "service", "scope"];
+
+/// Accounting counters of a control group, see `man systemd.resource-control`.
+///
+/// Counters are `None` if the corresponding controller is not enabled for the group.
+pub struct CgroupUsage {
+    /// The path below the hierarchy root, unique unlike unit names of nested user managers.
+    pub path: String,
+    pub unit_name: String,
+    /// CPU time consumed since the group was created.
+    pub cpu_usage: Duration,
+    /// Memory currently used in bytes.
+    pub memory: Option<u64>,
+    /// Bytes read from block devices since the group was created.
+    pub io_read: Option<u64>,
+    /// Bytes written to block devices since the group was created.
+    pub io_write: Option<u64>,
+    /// Number of tasks, i.e. processes and threads.
+    pub tasks: Option<u64>,
+}
+
+/// Reads the accounting counters of all slices, services and scopes.
+///
+/// # Errors
+/// - Returns an error if the unified control group hierarchy is not mounted at `/sys/fs/cgroup`.
+///
+/// # Related command
+/// The equivalent command is:
+/// ```
+/// systemd-cgtop --iterations=1
+/// ```
+///
+/// See `man systemd-cgtop` for more details.
+pub fn usage() -> std::io::Result<Vec<CgroupUsage>> {
+    let mut usage = Vec::new();
+    read_usage(Path::new(CGROUP_ROOT), "", &mut usage)?;
+    Ok(usage)
+}
+
+fn read_usage(directory: &Path, path: &str, usage: &mut Vec<CgroupUsage>) -> std::io::Result<()> {
+    for entry in fs::read_dir(directory)?.flatten() {
+        if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_monitored = MONITORED_UNIT_TYPES.contains(&unit_type(&name));
+        if !is_monitored {
+            continue;
+        }
+        let directory = entry.path();
+        let child_path = format!("{path}/{name}");
+        let read_counter = |file: &str| {
+            fs::read_to_string(directory.join(file))
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        let cpu_usage = fs::read_to_string(directory.join("cpu.stat"))
+            .ok()
+            .and_then(|cpu_stat| stat_field(&cpu_stat, "usage_usec"))
+            .map_or(Duration::ZERO, Duration::from_micros);
+        let (io_read, io_write) = fs::read_to_string(directory.join("io.stat"))
+            .ok()
+            .map(|io_stat| io_bytes(&io_stat))
+            .unzip();
+        usage.push(CgroupUsage {
+            path: child_path.clone(),
+            unit_name: name,
+            cpu_usage,
+            memory: read_counter("memory.current"),
+            io_read,
+            io_write,
+            tasks: read_counter("pids.current"),
+        });
+        // groups may vanish while being read
+        let _ = read_usage(&directory, &child_path, usage);
+    }
+    Ok(())
+}
+
+/// A value of a flat keyed file like `cpu.stat`, e.g. `usage_usec 1234`.
+fn stat_field(stat: &str, key: &str) -> Option<u64> {
+    stat.lines().find_map(|line| {
+        let (line_key, value) = line.split_once(' ')?;
+        (line_key == key).then(|| value.trim().parse().ok())?
+    })
+}
+
+/// Bytes read and written summed over all devices of `io.stat`, e.g. `8:0 rbytes=1 wbytes=2 rios=3 ...`.
+fn io_bytes(io_stat: &str) -> (u64, u64) {
+    io_stat
+        .split_whitespace()
+        .filter_map(|field| field.split_once('='))
+        .fold((0, 0), |(read, written), (key, value)| {
+            let value = value.parse::<u64>().unwrap_or_default();
+            match key {
+                "rbytes" => (read + value, written),
+                "wbytes" => (read, written + value),
+                _ => (read, written),
+            }
+        })
+}
@@ -1,14 +1,21 @@
+pub mod cgroup;
+pub mod status;
 pub(crate) mod unit;
 
+use crate::audit::{AuditEntry, Audited};
 use crate::systemd::unit::UnitObject;
+use std::collections::HashMap;
+use std::fs;
+use std::process::{Command, Output};
 use std::str::FromStr;
 use std::sync::LazyLock;
-use strum::EnumString;
+use std::time::SystemTime;
+use strum::{Display, EnumIter, EnumString};
 use systemctl::SystemCtl;
 
-#[derive(Debug, PartialEq, EnumString)]
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumString, EnumIter)]
 #[strum(serialize_all = "snake_case")]
-enum State {
+pub enum State {
     /// Started, bound, plugged in, ..., depending on the unit type.
     Active,
     /// Stopped, unbound, unplugged, ..., depending on the unit type.
@@ -25,7 +32,78 @@ enum State {
     Reloading,
 }
 
-#[derive(Debug, PartialEq)]
+impl State {
+    /// Whether the unit is changing between active and inactive or under maintenance.
+    pub fn is_transitional(&self) -> bool {
+        matches!(
+            self,
+            State::Activating | State::Deactivating | State::Maintenance | State::Reloading
+        )
+    }
+
+    /// The style class of table rows in this state, e.g. `state-failed`.
+    pub fn css_class(&self) -> String {
+        format!("state-{self}")
+    }
+
+    pub fn icon_name(&self) -> &'static str {
+        match self {
+            State::Active => "object-select-symbolic",
+            State::Inactive => "media-playback-stop-symbolic",
+            State::Failed => "dialog-error-symbolic",
+            State::Activating => "go-up-symbolic",
+            State::Deactivating => "go-down-symbolic",
+            State::Maintenance => "emblem-system-symbolic",
+            State::Reloading => "view-refresh-symbolic",
+        }
+    }
+}
+
+/// Whether the unit configuration was loaded, see `LOAD` in `man systemctl`.
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumString, EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum LoadState {
+    Loaded,
+    /// The unit file does not exist, the unit is only referenced.
+    NotFound,
+    /// The unit file has an invalid setting.
+    BadSetting,
+    /// The unit file could not be loaded.
+    Error,
+    /// The unit is masked and cannot be started.
+    Masked,
+    /// The unit was merged into another unit.
+    Merged,
+    /// The unit was not loaded yet.
+    Stub,
+}
+
+impl LoadState {
+    /// The load states table rows are styled for, the others look like loaded units.
+    pub const STYLED: [LoadState; 3] = [LoadState::NotFound, LoadState::Masked, LoadState::Error];
+
+    /// The style class of table rows in this load state, `None` for units loaded without problems.
+    pub fn css_class(&self) -> Option<&'static str> {
+        match self {
+            LoadState::NotFound => Some("load-not-found"),
+            LoadState::Masked => Some("load-masked"),
+            LoadState::Error | LoadState::BadSetting => Some("load-error"),
+            LoadState::Loaded | LoadState::Merged | LoadState::Stub => None,
+        }
+    }
+
+    /// The icon shown instead of the one of the active state, `None` for units loaded without problems.
+    pub fn icon_name(&self) -> Option<&'static str> {
+        match self {
+            LoadState::NotFound => Some("dialog-question-symbolic"),
+            LoadState::Masked => Some("action-unavailable-symbolic"),
+            LoadState::Error | LoadState::BadSetting => Some("dialog-warning-symbolic"),
+            LoadState::Loaded | LoadState::Merged | LoadState::Stub => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumIter)]
 pub enum SystemCtrlAction {
     Start,
     Stop,
@@ -35,19 +113,144 @@ pub enum SystemCtrlAction {
 }
 
 impl SystemCtrlAction {
+    /// The actions offered for the unit, none for states `systemctl` reports that are not known here.
     pub fn available_actions(unit_object: &UnitObject) -> Vec<SystemCtrlAction> {
-        let state: State = State::from_str(unit_object.state().as_str()).unwrap();
+        let Ok(state) = State::from_str(unit_object.state().as_str()) else {
+            return vec![];
+        };
         use crate::systemd::SystemCtrlAction::*;
         match state {
             State::Active => vec![Stop, Restart, Disable],
             State::Inactive => vec![Start, Enable],
-            State::Failed => vec![],
+            // stopping resets the failed state
+            State::Failed => vec![Start, Restart, Stop],
             State::Activating => vec![],
             State::Deactivating => vec![],
             State::Maintenance => vec![],
             State::Reloading => vec![],
         }
     }
+
+    /// The `systemctl` command of this action, also used to name its window actions, e.g. `win.unit-start`.
+    pub fn verb(&self) -> &'static str {
+        match self {
+            SystemCtrlAction::Start => "start",
+            SystemCtrlAction::Stop => "stop",
+            SystemCtrlAction::Restart => "restart",
+            SystemCtrlAction::Enable => "enable",
+            SystemCtrlAction::Disable => "disable",
+        }
+    }
+
+    /// Whether the action interrupts running units or changes what is started on boot.
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            SystemCtrlAction::Stop | SystemCtrlAction::Restart | SystemCtrlAction::Disable
+        )
+    }
+
+    /// The property holding the state the action changes, the active state or the unit file state.
+    pub fn state_property(&self) -> &'static str {
+        match self {
+            SystemCtrlAction::Start | SystemCtrlAction::Stop | SystemCtrlAction::Restart => {
+                "ActiveState"
+            }
+            SystemCtrlAction::Enable | SystemCtrlAction::Disable => "UnitFileState",
+        }
+    }
+
+    /// The state of each unit in the property changed by the action, `unknown` if it could not be read.
+    pub fn states(&self, unit_names: &[String]) -> Vec<String> {
+        let property = self.state_property();
+        match show_properties(unit_names, &[property.to_string()]) {
+            Ok(values) => values
+                .into_iter()
+                .map(|mut values| {
+                    values
+                        .remove(property)
+                        .filter(|state| !state.is_empty())
+                        .unwrap_or_else(|| String::from("unknown"))
+                })
+                .collect(),
+            Err(_) => vec![String::from("unknown"); unit_names.len()],
+        }
+    }
+
+    /// The action reverting this action for a unit that was in `previous_state` before, `None` if the action did
+    /// not change the state, e.g. stopping a unit that was inactive already, or can not be reverted.
+    pub fn undo(&self, previous_state: &str) -> Option<SystemCtrlAction> {
+        match self {
+            SystemCtrlAction::Start => {
+                matches!(previous_state, "inactive" | "failed").then_some(SystemCtrlAction::Stop)
+            }
+            SystemCtrlAction::Stop => {
+                matches!(previous_state, "active" | "activating" | "reloading")
+                    .then_some(SystemCtrlAction::Start)
+            }
+            SystemCtrlAction::Restart => None,
+            SystemCtrlAction::Enable => {
+                (previous_state == "disabled").then_some(SystemCtrlAction::Disable)
+            }
+            SystemCtrlAction::Disable => matches!(previous_state, "enabled" | "enabled-runtime")
+                .then_some(SystemCtrlAction::Enable),
+        }
+    }
+
+    /// Actions available for all of the given units.
+    pub fn common_actions(unit_objects: &[UnitObject]) -> Vec<SystemCtrlAction> {
+        let mut actions = match unit_objects.first() {
+            Some(unit_object) => Self::available_actions(unit_object),
+            None => return vec![],
+        };
+        for unit_object in &unit_objects[1..] {
+            let available_actions = Self::available_actions(unit_object);
+            actions.retain(|action| available_actions.contains(action));
+        }
+        actions
+    }
+
+    /// Runs the action for the given units and blocks until their jobs have finished.
+    pub fn run(&self, unit_names: &[String]) -> Audited<Vec<JobResult>> {
+        match self {
+            SystemCtrlAction::Start => start(unit_names),
+            SystemCtrlAction::Stop => stop(unit_names),
+            SystemCtrlAction::Restart => restart(unit_names),
+            SystemCtrlAction::Enable => enable(unit_names),
+            SystemCtrlAction::Disable => disable(unit_names),
+        }
+    }
+}
+
+/// Result of a systemd job, see `JobRemoved` in `man org.freedesktop.systemd1`.
+#[derive(Debug, PartialEq, Clone, Copy, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum JobResult {
+    /// The job finished successfully.
+    Done,
+    /// The job failed.
+    Failed,
+    /// The job timed out.
+    Timeout,
+    /// The job was canceled before it finished.
+    Canceled,
+    /// A job this job depended on failed, so it was never executed.
+    Dependency,
+}
+
+impl JobResult {
+    /// Maps the message `systemctl` prints when waiting for a job that did not succeed.
+    fn from_stderr(stderr: &str) -> JobResult {
+        if stderr.contains("canceled") {
+            JobResult::Canceled
+        } else if stderr.contains("timed out") || stderr.contains("timeout was exceeded") {
+            JobResult::Timeout
+        } else if stderr.contains("dependency job") {
+            JobResult::Dependency
+        } else {
+            JobResult::Failed
+        }
+    }
 }
 
 static SYSTEM_CTL: LazyLock<SystemCtl, fn() -> SystemCtl> = LazyLock::new(|| {
@@ -66,7 +269,7 @@ static SYSTEM_CTL: LazyLock<SystemCtl, fn() -> SystemCtl> = LazyLock::new(|| {
 /// It wraps the `systemctl list-units` command, which provides detailed information about all units.
 ///
 /// # Returns
-/// - A `Vec<UnitObject>` containing all units.
+/// - A `Result<Vec<UnitObject>>` containing all units.
 ///
 /// # Errors
 /// - Returns an error if the units could not be listed.
@@ -79,138 +282,198 @@ static SYSTEM_CTL: LazyLock<SystemCtl, fn() -> SystemCtl> = LazyLock::new(|| {
 /// This command will list all units currently loaded in memory, including information such as load state, active state, and sub-state.
 ///
 /// See `man systemctl` for more details.
-pub fn units() -> Vec<UnitObject> {
-    SYSTEM_CTL
-        .list_units_full(None, None, None)
-        .unwrap()
+pub fn units() -> std::io::Result<Vec<UnitObject>> {
+    Ok(SYSTEM_CTL
+        .list_units_full(None, None, None)?
         .iter()
         .map(|u| UnitObject::new(u.to_owned()))
-        .collect::<Vec<UnitObject>>()
+        .collect::<Vec<UnitObject>>())
 }
 
-/// Starts the specified unit.
+/// Starts the specified units.
 ///
-/// This function attempts to start the given systemd unit.
+/// This function attempts to start the given systemd units with a single `systemctl` call,
+/// so privileges are only requested once.
 /// It wraps the `systemctl start` command, which activates a unit.
 ///
 /// # Parameters
-/// - `unit`: The unit object to be started.
+/// - `unit_names`: The names of the units to be started.
 ///
-/// # Errors
-/// - Returns an error if the unit could not be started.
+/// # Returns
+/// - The `JobResult` of each unit's start job, in the order of `unit_names`, once all jobs have finished,
+///   along with the error writing them to the audit log, if any.
 ///
 /// # Related `systemctl` command
 /// The equivalent systemctl command is:
 /// ```
-/// systemctl start [UNIT]
+/// systemctl start [UNIT...]
 /// ```
-/// This command will start (activate) the specified unit immediately.
+/// This command will start (activate) the specified units immediately.
 ///
 /// See `man systemctl` for more details.
-pub fn start(unit: UnitObject) {
-    SYSTEM_CTL
-        .start(unit.unit_name().as_str())
-        .expect("Could not start unit");
+pub fn start(unit_names: &[String]) -> Audited<Vec<JobResult>> {
+    run_jobs("start", unit_names)
 }
 
-/// Stops the specified unit.
+/// Stops the specified units.
 ///
-/// This function attempts to stop the given systemd unit.
+/// This function attempts to stop the given systemd units with a single `systemctl` call,
+/// so privileges are only requested once.
 /// It wraps the `systemctl stop` command, which deactivates a unit.
 ///
 /// # Parameters
-/// - `unit`: The unit object to be stopped.
+/// - `unit_names`: The names of the units to be stopped.
 ///
-/// # Errors
-/// - Returns an error if the unit could not be stopped.
+/// # Returns
+/// - The `JobResult` of each unit's stop job, in the order of `unit_names`, once all jobs have finished,
+///   along with the error writing them to the audit log, if any.
 ///
 /// # Related `systemctl` command
 /// The equivalent systemctl command is:
 /// ```
-/// systemctl stop [UNIT]
+/// systemctl stop [UNIT...]
 /// ```
-/// This command will stop (deactivate) the specified unit immediately.
+/// This command will stop (deactivate) the specified units immediately.
 ///
 /// See `man systemctl` for more details.
-pub fn stop(unit: UnitObject) {
-    SYSTEM_CTL
-        .stop(unit.unit_name().as_str())
-        .expect("Could not stop unit");
+pub fn stop(unit_names: &[String]) -> Audited<Vec<JobResult>> {
+    run_jobs("stop", unit_names)
 }
 
-/// Restarts the specified unit.
+/// Restarts the specified units.
 ///
-/// This function attempts to restart the given systemd unit.
+/// This function attempts to restart the given systemd units with a single `systemctl` call,
+/// so privileges are only requested once.
 /// It wraps the `systemctl restart` command, which stops and then starts a unit.
 ///
 /// # Parameters
-/// - `unit`: The unit object to be restarted.
+/// - `unit_names`: The names of the units to be restarted.
 ///
-/// # Errors
-/// - Returns an error if the unit could not be restarted.
+/// # Returns
+/// - The `JobResult` of each unit's restart job, in the order of `unit_names`, once all jobs have finished,
+///   along with the error writing them to the audit log, if any.
 ///
 /// # Related `systemctl` command
 /// The equivalent systemctl command is:
 /// ```
-/// systemctl restart [UNIT]
+/// systemctl restart [UNIT...]
 /// ```
-/// This command will stop and then start the specified unit.
+/// This command will stop and then start the specified units.
 ///
 /// See `man systemctl` for more details.
-pub fn restart(unit: UnitObject) {
-    SYSTEM_CTL
-        .restart(unit.unit_name().as_str())
-        .expect("Could not restart unit");
+pub fn restart(unit_names: &[String]) -> Audited<Vec<JobResult>> {
+    run_jobs("restart", unit_names)
 }
 
-/// Enables the specified unit.
+/// Enables the specified units.
 ///
-/// This function attempts to enable the given systemd unit.
+/// This function attempts to enable the given systemd units with a single `systemctl` call,
+/// so privileges are only requested once.
 /// It wraps the `systemctl enable` command, which allows a unit to be started on boot.
 ///
 /// # Parameters
-/// - `unit`: The unit object to be enabled.
+/// - `unit_names`: The names of the units to be enabled.
 ///
-/// # Errors
-/// - Returns an error if the unit could not be enabled.
+/// # Returns
+/// - The `JobResult` of each unit's enable job, in the order of `unit_names`, once all jobs have finished,
+///   along with the error writing them to the audit log, if any.
 ///
 /// # Related `systemctl` command
 /// The equivalent systemctl command is:
 /// ```
-/// systemctl enable [UNIT]
+/// systemctl enable [UNIT...]
 /// ```
-/// This command will enable the specified unit, making it start automatically on boot.
+/// This command will enable the specified units, making them start automatically on boot.
 ///
 /// See `man systemctl` for more details.
-pub fn enable(unit: UnitObject) {
-    SYSTEM_CTL
-        .enable(unit.unit_name().as_str())
-        .expect("Could not enable unit");
+pub fn enable(unit_names: &[String]) -> Audited<Vec<JobResult>> {
+    run_jobs("enable", unit_names)
 }
 
-/// Disables the specified unit.
+/// Disables the specified units.
 ///
-/// This function attempts to disable the given systemd unit.
+/// This function attempts to disable the given systemd units with a single `systemctl` call,
+/// so privileges are only requested once.
 /// It wraps the `systemctl disable` command, which prevents a unit from starting on boot.
 ///
 /// # Parameters
-/// - `unit`: The unit object to be disabled.
+/// - `unit_names`: The names of the units to be disabled.
 ///
-/// # Errors
-/// - Returns an error if the unit could not be disabled.
+/// # Returns
+/// - The `JobResult` of each unit's disable job, in the order of `unit_names`, once all jobs have finished,
+///   along with the error writing them to the audit log, if any.
 ///
 /// # Related `systemctl` command
 /// The equivalent systemctl command is:
 /// ```
-/// systemctl disable [UNIT]
+/// systemctl disable [UNIT...]
 /// ```
-/// This command will disable the specified unit, preventing it from starting automatically on boot.
+/// This command will disable the specified units, preventing them from starting automatically on boot.
 ///
 /// See `man systemctl` for more details.
-pub fn disable(unit: UnitObject) {
-    SYSTEM_CTL
-        .disable(unit.unit_name().as_str())
-        .expect("Could not disable unit");
+pub fn disable(unit_names: &[String]) -> Audited<Vec<JobResult>> {
+    run_jobs("disable", unit_names)
+}
+
+/// Runs `systemctl [VERB] [UNIT...]` and waits for the queued jobs to finish.
+///
+/// `systemctl` blocks until the jobs are removed from the queue and only reports their results on stderr,
+/// which is why this does not go through `SYSTEM_CTL`.
+fn run_jobs(verb: &str, unit_names: &[String]) -> Audited<Vec<JobResult>> {
+    let outcomes = wait_for_jobs(verb, unit_names);
+    let entries = unit_names
+        .iter()
+        .zip(&outcomes)
+        .map(|(unit_name, (result, error))| {
+            AuditEntry::new(unit_name, verb, &result.to_string(), error)
+        })
+        .collect::<Vec<_>>();
+    let results = outcomes.into_iter().map(|(result, _)| result).collect();
+    Audited::record(results, &entries)
+}
+
+/// Returns the result of each unit's job along with the error `systemctl` printed for it.
+fn wait_for_jobs(verb: &str, unit_names: &[String]) -> Vec<(JobResult, String)> {
+    let output = match Command::new("systemctl")
+        .arg(verb)
+        .args(unit_names)
+        .output()
+    {
+        Ok(output) => output,
+        Err(error) => return vec![(JobResult::Failed, error.to_string()); unit_names.len()],
+    };
+    if output.status.success() {
+        return vec![(JobResult::Done, String::new()); unit_names.len()];
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // errors not naming any unit, e.g. a denied authorization, affect all units
+    let common_errors = stderr
+        .lines()
+        .filter(|line| {
+            !unit_names
+                .iter()
+                .any(|unit_name| line.contains(unit_name.as_str()))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    unit_names
+        .iter()
+        .map(|unit_name| {
+            let unit_errors = stderr
+                .lines()
+                .filter(|line| line.contains(unit_name.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !unit_errors.is_empty() {
+                (JobResult::from_stderr(&unit_errors), unit_errors)
+            } else if !common_errors.is_empty() {
+                (JobResult::Failed, common_errors.clone())
+            } else {
+                (JobResult::Done, String::new())
+            }
+        })
+        .collect()
 }
 
 /// Displays the contents of the unit file for the specified unit.
@@ -219,7 +482,7 @@ pub fn disable(unit: UnitObject) {
 /// It wraps the `systemctl cat` command, which outputs the unit file's content.
 ///
 /// # Parameters
-/// - `unit`: The unit object whose configuration file content is to be displayed.
+/// - `unit_name`: The name of the unit whose configuration file content is to be displayed.
 ///
 /// # Returns
 /// - A `Result<String>` containing the content of the unit file.
@@ -235,6 +498,317 @@ pub fn disable(unit: UnitObject) {
 /// This command will display the contents of the specified unit's file.
 ///
 /// See `man systemctl` for more details.
-pub fn cat(unit: UnitObject) -> std::io::Result<String> {
-    SYSTEM_CTL.cat(unit.unit_name().as_str())
+pub fn cat(unit_name: &str) -> std::io::Result<String> {
+    SYSTEM_CTL.cat(unit_name)
+}
+
+/// Shows the value of a single property of the specified unit.
+///
+/// # Parameters
+/// - `unit_name`: The name of the unit to be queried.
+/// - `property`: The name of the property, e.g. `FragmentPath`.
+///
+/// # Returns
+/// - A `Result<String>` containing the value, which is empty if the property is not set.
+///
+/// # Errors
+/// - Returns an error if `systemctl` could not be executed.
+///
+/// # Related `systemctl` command
+/// The equivalent systemctl command is:
+/// ```
+/// systemctl show --property=[PROPERTY] --value [UNIT]
+/// ```
+///
+/// See `man systemctl` for more details.
+pub fn show(unit_name: &str, property: &str) -> std::io::Result<String> {
+    let output = Command::new("systemctl")
+        .arg("show")
+        .arg(format!("--property={property}"))
+        .arg("--value")
+        .arg(unit_name)
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The overall state of the system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemState {
+    /// E.g. `running`, `degraded`, `maintenance` or `starting`.
+    pub state: String,
+    pub failed_units: u32,
+}
+
+/// Shows the overall state of the system and the number of failed units.
+///
+/// # Returns
+/// - A `Result<SystemState>` containing the state of the service manager.
+///
+/// # Errors
+/// - Returns an error if `systemctl` could not be executed or failed.
+///
+/// # Related `systemctl` command
+/// The equivalent systemctl command is:
+/// ```
+/// systemctl show --property=SystemState,NFailedUnits
+/// ```
+///
+/// See `man systemctl` for more details.
+pub fn system_state() -> std::io::Result<SystemState> {
+    // without a unit, `show` prints the properties of the service manager itself
+    let output = Command::new("systemctl")
+        .arg("show")
+        .arg("--property=SystemState,NFailedUnits")
+        .output()?;
+    if !output.status.success() {
+        return Err(command_error("systemctl show", &output));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let values = stdout
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .collect::<HashMap<_, _>>();
+    Ok(SystemState {
+        state: values
+            .get("SystemState")
+            .copied()
+            .unwrap_or_default()
+            .to_string(),
+        failed_units: values
+            .get("NFailedUnits")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+    })
+}
+
+/// Shows all properties of the specified unit.
+///
+/// # Parameters
+/// - `unit_name`: The name of the unit to be queried.
+///
+/// # Returns
+/// - A `Result<String>` containing one `NAME=VALUE` pair per line.
+///
+/// # Errors
+/// - Returns an error if `systemctl` could not be executed.
+///
+/// # Related `systemctl` command
+/// The equivalent systemctl command is:
+/// ```
+/// systemctl show [UNIT]
+/// ```
+///
+/// See `man systemctl` for more details.
+pub fn show_all(unit_name: &str) -> std::io::Result<String> {
+    let output = Command::new("systemctl")
+        .arg("show")
+        .arg(unit_name)
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Shows the given properties of many units at once.
+///
+/// # Parameters
+/// - `unit_names`: The names of the units to be queried.
+/// - `properties`: The names of the properties, e.g. `MainPID`.
+///
+/// # Returns
+/// - The property values of each unit, in the order of `unit_names`. Unknown properties are missing, as are all
+///   values of units `systemctl` did not report under their name.
+///
+/// # Errors
+/// - Returns an error if `systemctl` could not be executed or failed.
+///
+/// # Related `systemctl` command
+/// The equivalent systemctl command is:
+/// ```
+/// systemctl show --property=Id,[PROPERTY,...] [UNIT...]
+/// ```
+///
+/// See `man systemctl` for more details.
+pub fn show_properties(
+    unit_names: &[String],
+    properties: &[String],
+) -> std::io::Result<Vec<HashMap<String, String>>> {
+    if unit_names.is_empty() || properties.is_empty() {
+        return Ok(vec![HashMap::new(); unit_names.len()]);
+    }
+    let output = Command::new("systemctl")
+        .arg("show")
+        .arg(format!("--property=Id,{}", properties.join(",")))
+        .args(unit_names)
+        .output()?;
+    if !output.status.success() {
+        return Err(command_error("systemctl show", &output));
+    }
+    // one block of `NAME=VALUE` lines per unit, separated by empty lines, matched to the units by their `Id`
+    // as blocks of units systemctl cannot show are missing
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let values_by_id = stdout
+        .split("\n\n")
+        .map(|block| {
+            block
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>()
+        })
+        .filter_map(|mut values| Some((values.remove("Id")?, values)))
+        .collect::<HashMap<_, _>>();
+    Ok(unit_names
+        .iter()
+        .map(|unit_name| values_by_id.get(unit_name).cloned().unwrap_or_default())
+        .collect())
+}
+
+/// The error of a command that exited with a failure, with its error output as the message.
+pub fn command_error(command: &str, output: &Output) -> std::io::Error {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    std::io::Error::other(format!(
+        "{command} failed ({}): {}",
+        output.status,
+        stderr.trim()
+    ))
+}
+
+/// Shows the most recent journal entries of the specified unit.
+///
+/// # Parameters
+/// - `unit_name`: The name of the unit whose log is to be displayed.
+/// - `lines`: The maximum number of entries.
+///
+/// # Returns
+/// - A `Result<String>` containing one entry per line, oldest first.
+///
+/// # Errors
+/// - Returns an error if `journalctl` could not be executed.
+///
+/// # Related `journalctl` command
+/// The equivalent journalctl command is:
+/// ```
+/// journalctl --unit=[UNIT] --lines=[LINES]
+/// ```
+///
+/// See `man journalctl` for more details.
+pub fn journal(unit_name: &str, lines: u32) -> std::io::Result<String> {
+    let output = Command::new("journalctl")
+        .arg(format!("--unit={unit_name}"))
+        .arg(format!("--lines={lines}"))
+        .args(["--no-pager", "--output=short-iso"])
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Shows the units the specified unit depends on, as a tree.
+///
+/// # Parameters
+/// - `unit_name`: The name of the unit to be queried.
+///
+/// # Returns
+/// - A `Result<String>` containing one unit per line, indented by its depth in the tree.
+///
+/// # Errors
+/// - Returns an error if `systemctl` could not be executed.
+///
+/// # Related `systemctl` command
+/// The equivalent systemctl command is:
+/// ```
+/// systemctl list-dependencies [UNIT]
+/// ```
+///
+/// See `man systemctl` for more details.
+pub fn dependencies(unit_name: &str) -> std::io::Result<String> {
+    let output = Command::new("systemctl")
+        .args(["list-dependencies", "--no-pager"])
+        .arg(unit_name)
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Lists the units depending on any of the specified units.
+///
+/// # Parameters
+/// - `unit_names`: The names of the units to be queried.
+///
+/// # Returns
+/// - A `Result<Vec<String>>` containing the names of the dependent units, without the queried units themselves.
+///
+/// # Errors
+/// - Returns an error if `systemctl` could not be executed or fails, e.g. for an unknown unit.
+///
+/// # Related `systemctl` command
+/// The equivalent systemctl command is:
+/// ```
+/// systemctl list-dependencies --reverse --plain [UNIT]
+/// ```
+///
+/// See `man systemctl` for more details.
+pub fn reverse_dependencies(unit_names: &[String]) -> std::io::Result<Vec<String>> {
+    let mut dependents: Vec<String> = vec![];
+    for unit_name in unit_names {
+        let output = Command::new("systemctl")
+            .args([
+                "list-dependencies",
+                "--reverse",
+                "--plain",
+                "--no-legend",
+                "--no-pager",
+            ])
+            .arg(unit_name)
+            .output()?;
+        if !output.status.success() {
+            return Err(command_error("systemctl list-dependencies", &output));
+        }
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            // the name is the last column, after the optional state bullet
+            if let Some(dependent) = line.split_whitespace().last() {
+                let dependent = dependent.to_string();
+                if !unit_names.contains(&dependent) && !dependents.contains(&dependent) {
+                    dependents.push(dependent);
+                }
+            }
+        }
+    }
+    Ok(dependents)
+}
+
+/// Location of a unit file and its drop-ins on disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FragmentPath {
+    /// Empty for units without a unit file.
+    pub path: String,
+    /// Modification time of the file, `None` for units without a unit file or if it could not be read.
+    pub modified: Option<SystemTime>,
+    /// Paths and modification times of the drop-in files, which `systemctl cat` shows after the unit file.
+    pub drop_ins: Vec<(String, Option<SystemTime>)>,
+}
+
+impl FragmentPath {
+    /// Whether all files have a modification time, so changes to them can be told from the times.
+    pub fn is_cacheable(&self) -> bool {
+        (self.path.is_empty() || self.modified.is_some())
+            && self.drop_ins.iter().all(|(_, modified)| modified.is_some())
+    }
+}
+
+/// Looks up the unit file and the drop-ins of the specified unit and their modification times.
+///
+/// # Parameters
+/// - `unit_name`: The name of the unit to be queried.
+///
+/// # Errors
+/// - Returns an error if `systemctl` could not be executed.
+pub fn fragment_path(unit_name: &str) -> std::io::Result<FragmentPath> {
+    let modified = |path: &str| fs::metadata(path).and_then(|m| m.modified()).ok();
+    let path = show(unit_name, "FragmentPath")?;
+    let drop_ins = show(unit_name, "DropInPaths")?
+        .split_whitespace()
+        .map(|drop_in| (drop_in.to_string(), modified(drop_in)))
+        .collect();
+    Ok(FragmentPath {
+        modified: modified(&path),
+        path,
+        drop_ins,
+    })
 }
@@ -0,0 +1,124 @@
+use crate::systemd::cgroup::{self, Cgroup};
+use crate::systemd::{command_error, journal};
+use adw::glib;
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// Number of journal entries in the status, as many as `systemctl status` prints.
+const JOURNAL_LINES: u32 = 10;
+
+const PROPERTIES: [&str; 13] = [
+    "Description",
+    "LoadState",
+    "FragmentPath",
+    "UnitFileState",
+    "UnitFilePreset",
+    "ActiveState",
+    "SubState",
+    "StateChangeTimestampMonotonic",
+    "MainPID",
+    "TasksCurrent",
+    "MemoryCurrent",
+    "CPUUsageNSec",
+    "ControlGroup",
+];
+
+/// The runtime status of a unit, the information `systemctl status` prints.
+pub struct UnitStatus {
+    pub description: String,
+    pub load_state: String,
+    /// Path of the unit file, empty for units without one.
+    pub fragment_path: String,
+    /// Whether the unit file is enabled, e.g. `enabled` or `static`, empty for units without one.
+    pub unit_file_state: String,
+    /// The state `systemctl preset` would enable or disable the unit file to, empty if no preset applies.
+    pub vendor_preset: String,
+    pub active_state: String,
+    pub sub_state: String,
+    /// When the unit entered its current state, `None` if it never changed its state.
+    pub since: Option<SystemTime>,
+    pub main_pid: Option<u32>,
+    /// Accounting counters, `None` if accounting is disabled for the unit or it is not running.
+    pub tasks: Option<u64>,
+    pub memory: Option<u64>,
+    pub cpu_usage: Option<Duration>,
+    /// The control group with its processes, `None` if the unit is not running.
+    pub cgroup: Option<Cgroup>,
+    /// The latest journal entries, oldest first.
+    pub journal: String,
+}
+
+/// Collects the runtime status of the specified unit.
+///
+/// # Parameters
+/// - `unit_name`: The name of the unit to be queried.
+///
+/// # Errors
+/// - Returns an error if `systemctl` or `journalctl` could not be executed.
+///
+/// # Related `systemctl` command
+/// The equivalent systemctl command is:
+/// ```
+/// systemctl status [UNIT]
+/// ```
+///
+/// See `man systemctl` for more details.
+pub fn unit_status(unit_name: &str) -> std::io::Result<UnitStatus> {
+    let output = Command::new("systemctl")
+        .arg("show")
+        .arg(format!("--property={}", PROPERTIES.join(",")))
+        .arg(unit_name)
+        .output()?;
+    if !output.status.success() {
+        return Err(command_error("systemctl show", &output));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let values = stdout
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .collect::<HashMap<_, _>>();
+    let value = |property: &str| values.get(property).copied().unwrap_or_default();
+
+    let control_group = value("ControlGroup");
+    // the control group stays known while the unit is stopped, but no longer exists
+    let cgroup = (!control_group.is_empty())
+        .then(|| cgroup::cgroup(control_group).ok())
+        .flatten();
+
+    Ok(UnitStatus {
+        description: value("Description").to_string(),
+        load_state: value("LoadState").to_string(),
+        fragment_path: value("FragmentPath").to_string(),
+        unit_file_state: value("UnitFileState").to_string(),
+        vendor_preset: value("UnitFilePreset").to_string(),
+        active_state: value("ActiveState").to_string(),
+        sub_state: value("SubState").to_string(),
+        since: since(value("StateChangeTimestampMonotonic")),
+        main_pid: value("MainPID").parse().ok().filter(|pid| *pid != 0),
+        tasks: counter(value("TasksCurrent")),
+        memory: counter(value("MemoryCurrent")),
+        cpu_usage: counter(value("CPUUsageNSec")).map(Duration::from_nanos),
+        cgroup,
+        journal: journal(unit_name, JOURNAL_LINES)?,
+    })
+}
+
+/// Converts a monotonic timestamp in microseconds to the wall clock time, `None` for `0` if the state never changed.
+///
+/// The wall clock timestamps are only printed as Unix time by systemd 251 and later, the monotonic ones are plain
+/// numbers on all versions.
+fn since(monotonic_timestamp: &str) -> Option<SystemTime> {
+    let timestamp = monotonic_timestamp
+        .parse::<u64>()
+        .ok()
+        .filter(|timestamp| *timestamp != 0)?;
+    // both use `CLOCK_MONOTONIC`
+    let elapsed = (glib::monotonic_time() as u64).checked_sub(timestamp)?;
+    SystemTime::now().checked_sub(Duration::from_micros(elapsed))
+}
+
+/// Parses an accounting counter, which is `[not set]` or the maximum value if it is not available.
+fn counter(value: &str) -> Option<u64> {
+    value.parse().ok().filter(|value| *value != u64::MAX)
+}
@@ -2,6 +2,7 @@ use adw::glib;
 use adw::glib::Properties;
 use adw::prelude::ObjectExt;
 use adw::subclass::prelude::*;
+use std::collections::HashMap;
 use std::sync::Mutex;
 
 // Object holding the state
@@ -22,6 +23,21 @@ pub struct UnitObject {
 
     #[property(get, construct_only)]
     description: Mutex<String>,
+
+    /// Whether a job for this unit is currently running.
+    #[property(get, set)]
+    busy: Mutex<bool>,
+
+    /// Whether the unit is pinned to the top of the table.
+    #[property(get, set)]
+    favorite: Mutex<bool>,
+
+    /// Whether the resource usage of the unit is sampled, see `metrics::WATCHED_UNITS_KEY`.
+    #[property(get, set)]
+    watched: Mutex<bool>,
+
+    /// Further `systemctl show` properties shown as columns, see `UnitObject::unit_property`.
+    pub unit_properties: Mutex<HashMap<String, String>>,
 }
 
 // The central trait for subclassing a GObject
@@ -2,6 +2,8 @@ mod imp;
 
 use adw::glib;
 use adw::glib::Object;
+use adw::subclass::prelude::ObjectSubclassIsExt;
+use std::collections::HashMap;
 use systemctl::UnitService;
 
 glib::wrapper! {
@@ -18,4 +20,38 @@ impl UnitObject {
             .property("description", u.description)
             .build()
     }
+
+    /// The value of a `systemctl show` property loaded with `set_unit_properties`, empty if not loaded.
+    pub fn unit_property(&self, name: &str) -> String {
+        self.imp()
+            .unit_properties
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_unit_properties(&self, properties: HashMap<String, String>) {
+        self.imp()
+            .unit_properties
+            .lock()
+            .unwrap()
+            .extend(properties);
+    }
+}
+
+/// The unit type, the suffix of the unit name without the dot, e.g. `service`, empty if there is no suffix.
+pub fn unit_type(unit_name: &str) -> &str {
+    let (_, suffix) = split_name_and_suffix(unit_name);
+    suffix.strip_prefix('.').unwrap_or(suffix)
+}
+
+/// Split unit name and suffix in order to enable two layer sorting
+pub fn split_name_and_suffix(s: &str) -> (&str, &str) {
+    if let Some(idx) = s.rfind('.') {
+        (&s[..idx], &s[idx..])
+    } else {
+        (s, "")
+    }
 }
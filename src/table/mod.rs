@@ -1,57 +1,440 @@
-use crate::systemd::unit::UnitObject;
+use crate::filter::UnitFilter;
+use crate::group::GroupObject;
+use crate::query::Field;
+use crate::systemd::unit::{split_name_and_suffix, UnitObject};
+use crate::systemd::{LoadState, State, SystemCtrlAction};
+use crate::window;
 use adw::gdk::pango::EllipsizeMode;
-use adw::prelude::{Cast, CastNone, ListItemExt, WidgetExt};
+use adw::gdk::Rectangle;
+use adw::prelude::{
+    Cast, CastNone, ListItemExt, ListModelExt, ListModelExtManual, ObjectExt, PopoverExt,
+    SettingsExt, SettingsExtManual, SorterExt, ToVariant, WidgetExt,
+};
+use adw::{gio, glib};
 use gtk::glib::Object;
-use gtk::prelude::BoxExt;
+use gtk::prelude::{BoxExt, FilterExt, GObjectPropertyExpressionExt, GestureSingleExt};
 use gtk::{
-    ColumnView, ColumnViewColumn, CustomSorter, Label, ListItem, ListItemFactory, Ordering,
-    SignalListItemFactory, SortType,
+    ColumnView, ColumnViewColumn, ColumnViewSorter, CustomFilter, CustomSorter, GestureClick,
+    Image, Label, ListItem, ListItemFactory, Ordering, Orientation, PopoverMenu,
+    SignalListItemFactory, SortType, Spinner, TreeExpander, TreeListRow, Widget,
 };
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::Duration;
+use strum::IntoEnumIterator;
 
 type SplitFunction = Option<fn(&str) -> (&str, &str)>;
-type ColumnProperties<'a> = [(&'a str, fn(&UnitObject) -> String, SplitFunction)];
+type ColumnProperties<'a> = [(
+    &'a str,
+    fn(&UnitObject) -> String,
+    SplitFunction,
+    Option<Field>,
+)];
+/// Returns the char positions of the label text to highlight.
+type Highlighter = Rc<dyn Fn(&str) -> Vec<usize>>;
+/// Returns the value of a column for a unit.
+type Getter = Rc<dyn Fn(&UnitObject) -> String>;
+/// The bindings of the indicators of the bound rows, see `bind_indicators`.
+type IndicatorBindings = Rc<RefCell<Vec<(glib::WeakRef<Object>, Vec<glib::Binding>)>>>;
+
+/// Style class of the label showing the summary of a group.
+const GROUP_SUMMARY_CLASS: &str = "group-summary";
+/// Style class of the spinner and the star, whose visibility is bound to the unit, see `bind_indicators`.
+const INDICATOR_CLASS: &str = "unit-indicator";
+
+/// Settings keys of the column layout.
+const COLUMNS_KEY: &str = "columns";
+const SORT_COLUMN_KEY: &str = "sort-column";
+const SORT_ASCENDING_KEY: &str = "sort-ascending";
+
+/// Time without further width changes after which a resized column is saved.
+const WIDTH_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+static COLUMN_PROPERTIES: &ColumnProperties = &[
+    (
+        "UNIT",
+        UnitObject::unit_name,
+        Some(split_name_and_suffix),
+        Some(Field::Name),
+    ),
+    ("LOAD", UnitObject::load, None, None),
+    ("ACTIVE", UnitObject::state, None, None),
+    ("SUB", UnitObject::sub_state, None, None),
+    (
+        "DESCRIPTION",
+        UnitObject::description,
+        None,
+        Some(Field::Description),
+    ),
+];
 
 /// Sets up the columns for the given `ColumnView` widget.
 ///
 /// This function takes a `ColumnView` widget and adds multiple columns to it, each representing a different property of a `UnitObject`.
-/// The columns include "UNIT", "LOAD", "ACTIVE", "SUB", and "DESCRIPTION". Each column is associated with a getter function that
-/// extracts the appropriate property from a `UnitObject`. The "UNIT" column is sorted in ascending order by default.
+/// The columns include "UNIT", "LOAD", "ACTIVE", "SUB", and "DESCRIPTION" and any further `systemctl show` property the
+/// user added. Order, visibility and width of the columns and the sort column are restored from and persisted in the
+/// settings. The "UNIT" column is sorted in ascending order by default.
+///
+/// The "UNIT" and "DESCRIPTION" labels highlight the characters matched by the search query and are updated whenever
+/// the `filter` changes.
 ///
 /// # Arguments
 /// * `column_view` - A reference to a `ColumnView` widget to which columns will be added.
+/// * `filter` - The filter of the rows, notifying about changes of the query.
+/// * `unit_filter` - The state of the filter holding the query.
+/// * `settings` - The settings holding the column layout.
 ///
 /// # GTK-RS
 /// This function uses GTK-RS to create columns for a `ColumnView` widget. It utilizes `SignalListItemFactory` to create list item factories,
 /// `CustomSorter` to define custom sorting for columns, and `ColumnViewColumn` to represent individual columns in the `ColumnView`.
-pub fn setup_columns(column_view: &ColumnView) {
-    let properties: &ColumnProperties = &[
-        ("UNIT", UnitObject::unit_name, Some(split_name_and_suffix)),
-        ("LOAD", UnitObject::load, None),
-        ("ACTIVE", UnitObject::state, None),
-        ("SUB", UnitObject::sub_state, None),
-        ("DESCRIPTION", UnitObject::description, None),
-    ];
-
-    for (title, getter, split_func) in properties {
-        let factory = create_factory(*getter);
-        let column = with_expand(title, factory, *getter, *split_func);
-        column.set_expand(false);
+pub fn setup_columns(
+    column_view: &ColumnView,
+    filter: &CustomFilter,
+    unit_filter: &Rc<RefCell<UnitFilter>>,
+    settings: &gio::Settings,
+) {
+    for config in ColumnConfig::load(settings) {
+        let column = build_column(&config.id, Some((filter, unit_filter)), settings);
+        column.set_visible(config.visible);
+        if config.width > 0 {
+            column.set_fixed_width(config.width);
+            column.set_expand(false);
+        }
         column_view.append_column(&column);
-        // sort by unit column by default
-        if "UNIT".eq(*title) {
-            column_view.sort_by_column(Some(&column), SortType::Ascending);
+    }
+
+    let sort_column = find_column(column_view, &settings.string(SORT_COLUMN_KEY))
+        .or_else(|| find_column(column_view, "UNIT"));
+    let sort_type = if settings.boolean(SORT_ASCENDING_KEY) {
+        SortType::Ascending
+    } else {
+        SortType::Descending
+    };
+    column_view.sort_by_column(sort_column.as_ref(), sort_type);
+
+    // persist the layout once it is restored, columns are added, removed and reordered via the columns model
+    column_view.columns().connect_items_changed(glib::clone!(
+        #[weak]
+        column_view,
+        #[strong]
+        settings,
+        move |_, _, _, _| ColumnConfig::save(&column_view, &settings)
+    ));
+    if let Some(sorter) = column_view.sorter().and_downcast::<ColumnViewSorter>() {
+        sorter.connect_changed(glib::clone!(
+            #[weak]
+            column_view,
+            #[strong]
+            settings,
+            move |sorter, _| {
+                let sort_column = sorter
+                    .primary_sort_column()
+                    .and_then(|column| column.id())
+                    .unwrap_or_default();
+                let ascending = sorter.primary_sort_order() == SortType::Ascending;
+                let result = settings
+                    .set_string(SORT_COLUMN_KEY, &sort_column)
+                    .and_then(|_| settings.set_boolean(SORT_ASCENDING_KEY, ascending));
+                if let Err(error) = result {
+                    window::toast(
+                        &column_view,
+                        &format!("Failed to save the sort column: {error}"),
+                    );
+                }
+            }
+        ));
+    }
+}
+
+/// Adds a column showing a `systemctl show` property, or shows it again if it was hidden.
+///
+/// The values need to be loaded into the units with `UnitObject::set_unit_properties` first.
+pub fn add_property_column(column_view: &ColumnView, property: &str, settings: &gio::Settings) {
+    match find_column(column_view, property) {
+        Some(column) => column.set_visible(true),
+        None => column_view.append_column(&build_column(property, None, settings)),
+    }
+}
+
+/// Removes a property column, the built-in columns can only be hidden.
+pub fn remove_property_column(column_view: &ColumnView, property: &str) {
+    if is_property_column(property) {
+        if let Some(column) = find_column(column_view, property) {
+            column_view.remove_column(&column);
         }
     }
 }
 
-fn create_factory(getter: fn(&UnitObject) -> String) -> SignalListItemFactory {
+/// The `systemctl show` properties of the persisted columns, which need to be loaded along with the units.
+pub fn property_columns(settings: &gio::Settings) -> Vec<String> {
+    ColumnConfig::load(settings)
+        .into_iter()
+        .map(|config| config.id)
+        .filter(|id| is_property_column(id))
+        .collect()
+}
+
+/// Whether the column shows a `systemctl show` property added by the user, rather than a built-in column.
+pub fn is_property_column(id: &str) -> bool {
+    !COLUMN_PROPERTIES
+        .iter()
+        .any(|(title, _, _, _)| id == *title)
+}
+
+pub fn find_column(column_view: &ColumnView, id: &str) -> Option<ColumnViewColumn> {
+    column_view
+        .columns()
+        .iter::<ColumnViewColumn>()
+        .flatten()
+        .find(|column| column.id().is_some_and(|column_id| column_id == id))
+}
+
+/// Returns the headers of the visible columns and the rows exactly as currently shown, filtered and sorted.
+pub fn visible_rows(column_view: &ColumnView) -> (Vec<String>, Vec<Vec<String>>) {
+    let getters = column_view
+        .columns()
+        .iter::<ColumnViewColumn>()
+        .flatten()
+        .filter(|column| column.is_visible())
+        .filter_map(|column| column.id())
+        .map(|id| (id.to_string(), column_getter(&id)))
+        .collect::<Vec<_>>();
+    let headers = getters.iter().map(|(title, _)| title.clone()).collect();
+    let rows = column_view
+        .model()
+        .map(|model| {
+            model
+                .iter::<Object>()
+                .flatten()
+                .filter_map(|item| unit_object_of(&item))
+                .map(|unit_object| {
+                    getters
+                        .iter()
+                        .map(|(_, getter)| getter(&unit_object))
+                        .collect()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    (headers, rows)
+}
+
+/// Persisted layout of a column, the columns are persisted in their order.
+struct ColumnConfig {
+    /// Title of a built-in column or name of a `systemctl show` property.
+    id: String,
+    visible: bool,
+    /// Fixed width in pixels, or -1 for the natural width.
+    width: i32,
+}
+
+impl ColumnConfig {
+    fn load(settings: &gio::Settings) -> Vec<ColumnConfig> {
+        let mut configs = settings
+            .get::<Vec<(String, bool, i32)>>(COLUMNS_KEY)
+            .into_iter()
+            .map(|(id, visible, width)| ColumnConfig { id, visible, width })
+            .collect::<Vec<_>>();
+        // built-in columns can not be removed, but may be missing from outdated settings
+        for (title, _, _, _) in COLUMN_PROPERTIES {
+            if !configs.iter().any(|config| config.id == *title) {
+                configs.push(ColumnConfig {
+                    id: title.to_string(),
+                    visible: true,
+                    width: -1,
+                });
+            }
+        }
+        configs
+    }
+
+    fn save(column_view: &ColumnView, settings: &gio::Settings) {
+        let value = column_view
+            .columns()
+            .iter::<ColumnViewColumn>()
+            .flatten()
+            .filter_map(|column| {
+                let id = column.id()?.to_string();
+                Some((id, column.is_visible(), column.fixed_width()))
+            })
+            .collect::<Vec<_>>();
+        if let Err(error) = settings.set(COLUMNS_KEY, value) {
+            window::toast(column_view, &format!("Failed to save the columns: {error}"));
+        }
+    }
+}
+
+/// Builds the column with its factory and sorter, highlighting matches of the query in built-in columns if a
+/// `filter` is given.
+fn build_column(
+    id: &str,
+    filter: Option<(&CustomFilter, &Rc<RefCell<UnitFilter>>)>,
+    settings: &gio::Settings,
+) -> ColumnViewColumn {
+    let (split_func, highlight_field) = COLUMN_PROPERTIES
+        .iter()
+        .find(|(title, _, _, _)| id == *title)
+        .map_or((None, None), |(_, _, split_func, highlight_field)| {
+            (*split_func, *highlight_field)
+        });
+    let getter = column_getter(id);
+    let highlighter = match (filter, highlight_field) {
+        (Some((filter, unit_filter)), Some(field)) => {
+            let unit_filter = Rc::clone(unit_filter);
+            let highlighter =
+                Rc::new(move |text: &str| unit_filter.borrow().query.highlights(field, text))
+                    as Highlighter;
+            Some((filter, highlighter))
+        }
+        _ => None,
+    };
+    // the unit column shows a spinner while a job for the unit is running
+    let factory = create_factory(Rc::clone(&getter), "UNIT".eq(id), highlighter);
+    let column = with_expand(id, factory, getter, split_func);
+    column.set_id(Some(id));
+    column.set_resizable(true);
+
+    let header_menu = gio::Menu::new();
+    let item = gio::MenuItem::new(Some("Hide Column"), None);
+    item.set_action_and_target_value(Some("win.column-hide"), Some(&id.to_variant()));
+    header_menu.append_item(&item);
+    header_menu.append(Some("Columns…"), Some("win.columns_show"));
+    column.set_header_menu(Some(&header_menu));
+
+    // persist resized and hidden columns, unless they are still being set up
+    let save = glib::clone!(
+        #[strong]
+        settings,
+        move |column: &ColumnViewColumn| {
+            if let Some(column_view) = column.column_view() {
+                ColumnConfig::save(&column_view, &settings);
+            }
+        }
+    );
+    // the width changes with every pixel while dragging, so it is saved once the drag has paused
+    let pending_save = Rc::new(RefCell::new(None::<glib::SourceId>));
+    column.connect_fixed_width_notify(glib::clone!(
+        #[strong]
+        save,
+        move |column| {
+            if let Some(source) = pending_save.take() {
+                source.remove();
+            }
+            let source = glib::timeout_add_local_once(
+                WIDTH_SAVE_DELAY,
+                glib::clone!(
+                    #[weak]
+                    column,
+                    #[strong]
+                    pending_save,
+                    #[strong]
+                    save,
+                    move || {
+                        pending_save.take();
+                        save(&column);
+                    }
+                ),
+            );
+            pending_save.replace(Some(source));
+        }
+    ));
+    column.connect_visible_notify(save);
+    column
+}
+
+fn column_getter(id: &str) -> Getter {
+    match COLUMN_PROPERTIES
+        .iter()
+        .find(|(title, _, _, _)| id == *title)
+    {
+        Some((_, getter, _, _)) => Rc::new(*getter),
+        None => {
+            let property = id.to_string();
+            Rc::new(move |unit_object: &UnitObject| unit_object.unit_property(&property))
+        }
+    }
+}
+
+fn create_factory(
+    getter: Getter,
+    with_spinner: bool,
+    highlighter: Option<(&CustomFilter, Highlighter)>,
+) -> SignalListItemFactory {
     let factory = SignalListItemFactory::new();
-    factory.connect_setup(|_, list_item| setup_factory(list_item));
-    factory.connect_bind(move |_, list_item| build_label(list_item, getter));
+    factory.connect_setup(move |_, list_item| setup_factory(list_item, with_spinner));
+    if with_spinner {
+        // the spinner and the star follow the unit while it is bound, rows are not rebound when it changes
+        let bindings: IndicatorBindings = Rc::default();
+        let bind_bindings = Rc::clone(&bindings);
+        factory.connect_bind(move |_, list_item| {
+            let item_bindings = bind_indicators(list_item);
+            bind_bindings
+                .borrow_mut()
+                .push((list_item.downgrade(), item_bindings));
+        });
+        factory.connect_unbind(move |_, list_item| {
+            bindings.borrow_mut().retain(|(bound_item, item_bindings)| {
+                let bound = bound_item
+                    .upgrade()
+                    .is_some_and(|bound_item| bound_item != *list_item);
+                if !bound {
+                    for binding in item_bindings {
+                        binding.unbind();
+                    }
+                }
+                bound
+            });
+        });
+    }
+    let Some((filter, highlighter)) = highlighter else {
+        factory.connect_bind(move |_, list_item| build_label(list_item, &getter, None));
+        return factory;
+    };
+
+    // bound rows are not bound again when the query changes, so their highlighting is updated from the filter
+    let bound_items: Rc<RefCell<Vec<glib::WeakRef<Object>>>> = Rc::default();
+    let handler = filter.connect_changed(glib::clone!(
+        #[strong]
+        bound_items,
+        #[strong]
+        getter,
+        #[strong]
+        highlighter,
+        move |_, _| {
+            for list_item in bound_items
+                .borrow()
+                .iter()
+                .filter_map(glib::WeakRef::upgrade)
+            {
+                build_label(&list_item, &getter, Some(&highlighter));
+            }
+        }
+    ));
+    // the factory lives as long as its column, e.g. until the columns are set up again
+    let weak_filter = filter.downgrade();
+    factory.add_weak_ref_notify_local(move || {
+        if let Some(filter) = weak_filter.upgrade() {
+            filter.disconnect(handler);
+        }
+    });
+
+    let bind_items = Rc::clone(&bound_items);
+    factory.connect_bind(move |_, list_item| {
+        bind_items.borrow_mut().push(list_item.downgrade());
+        build_label(list_item, &getter, Some(&highlighter));
+    });
+    factory.connect_unbind(move |_, list_item| {
+        bound_items.borrow_mut().retain(|bound_item| {
+            bound_item
+                .upgrade()
+                .is_some_and(|bound_item| bound_item != *list_item)
+        });
+    });
     factory
 }
 
-fn setup_factory(list_item: &Object) {
+fn setup_factory(list_item: &Object, with_spinner: bool) {
     let list_item = list_item
         .downcast_ref::<ListItem>()
         .expect("Needs to be ListItem");
@@ -59,49 +442,282 @@ fn setup_factory(list_item: &Object) {
     let label = Label::new(None);
     label.set_ellipsize(EllipsizeMode::Middle);
     let boxx = gtk::Box::default();
+    if with_spinner {
+        // the state icon, so states are told apart by more than colour
+        let state_icon = Image::new();
+        state_icon.set_margin_end(6);
+        boxx.append(&state_icon);
+    }
     boxx.append(&label);
+    if with_spinner {
+        let spinner = Spinner::new();
+        spinner.set_margin_start(6);
+        spinner.add_css_class(INDICATOR_CLASS);
+        boxx.append(&spinner);
+        let favorite = Image::from_icon_name("starred-symbolic");
+        favorite.set_margin_start(6);
+        favorite.set_tooltip_text(Some("Pinned to the top"));
+        favorite.add_css_class(INDICATOR_CLASS);
+        boxx.append(&favorite);
+
+        // the summary of a group, shown instead of the unit in the grouped view
+        let group_label = Label::new(None);
+        group_label.add_css_class("heading");
+        group_label.add_css_class(GROUP_SUMMARY_CLASS);
+        list_item
+            .property_expression_weak("item")
+            .chain_property::<TreeListRow>("item")
+            .chain_property::<GroupObject>("summary")
+            .bind(&group_label, "label", Widget::NONE);
+        boxx.append(&group_label);
+    }
+
+    // open the context menu of the row on right click
+    let gesture = GestureClick::new();
+    gesture.set_button(3);
+    gesture.connect_pressed(glib::clone!(
+        #[weak]
+        list_item,
+        #[weak]
+        boxx,
+        move |_, _, x, y| {
+            if let Some(unit_object) = list_item.item().and_then(|item| unit_object_of(&item)) {
+                show_context_menu(&boxx, &unit_object, x, y);
+            }
+        }
+    ));
+    boxx.add_controller(gesture);
 
-    list_item.set_child(Some(&boxx));
+    if with_spinner {
+        // the unit column indents the units of a group and expands the groups
+        let expander = TreeExpander::new();
+        expander.set_child(Some(&boxx));
+        list_item.set_child(Some(&expander));
+    } else {
+        list_item.set_child(Some(&boxx));
+    }
 }
 
-fn build_label(list_item: &Object, transform_fn: fn(&UnitObject) -> String) {
-    let unit_object = list_item
+/// Binds the spinner and the star of the unit column to `busy` and `favorite` of the unit of the row.
+fn bind_indicators(list_item: &Object) -> Vec<glib::Binding> {
+    let list_item = list_item
         .downcast_ref::<ListItem>()
-        .expect("Needs to be ListItem")
-        .item()
-        .and_downcast::<UnitObject>()
-        .expect("The item has to be an `UnitObject`.");
-
+        .expect("Needs to be ListItem");
+    let Some(unit_object) = list_item.item().and_then(|item| unit_object_of(&item)) else {
+        // the rows of groups hide the indicators, see `show_group_summary`
+        return Vec::new();
+    };
     let boxx = list_item
-        .downcast_ref::<ListItem>()
-        .expect("Needs to be ListItem")
         .child()
+        .and_downcast::<TreeExpander>()
+        .and_then(|expander| expander.child())
         .and_downcast::<gtk::Box>()
-        .expect("The child has to be a `Box`.");
+        .expect("The child has to be a `Box` in a `TreeExpander`.");
+    let spinner = std::iter::successors(boxx.first_child(), Widget::next_sibling)
+        .find_map(|widget| widget.downcast::<Spinner>().ok())
+        .expect("The unit column has a `Spinner`.");
+    let favorite = spinner
+        .next_sibling()
+        .expect("The star follows the `Spinner`.");
+    vec![
+        unit_object
+            .bind_property("busy", &spinner, "spinning")
+            .sync_create()
+            .build(),
+        unit_object
+            .bind_property("busy", &spinner, "visible")
+            .sync_create()
+            .build(),
+        unit_object
+            .bind_property("favorite", &favorite, "visible")
+            .sync_create()
+            .build(),
+    ]
+}
 
-    boxx.set_tooltip_text(Some(unit_object.unit_name().as_str()));
+/// The unit of a row, which is wrapped in a `TreeListRow` in the grouped view. `None` for the rows of groups.
+pub fn unit_object_of(item: &Object) -> Option<UnitObject> {
+    match item.downcast_ref::<TreeListRow>() {
+        Some(row) => row.item().and_downcast(),
+        None => item.downcast_ref::<UnitObject>().cloned(),
+    }
+}
 
-    if !unit_object.state().eq("active") {
-        WidgetExt::add_css_class(&boxx, "inactive");
+/// Shows a menu with the actions available for the unit, dispatched via the `win.unit-*` actions.
+fn show_context_menu(parent: &gtk::Box, unit_object: &UnitObject, x: f64, y: f64) {
+    let target = unit_object.unit_name().to_variant();
+    let menu = gio::Menu::new();
+    for action in SystemCtrlAction::available_actions(unit_object) {
+        let item = gio::MenuItem::new(Some(&action.to_string()), None);
+        let action_name = format!("win.unit-{}", action.verb());
+        item.set_action_and_target_value(Some(&action_name), Some(&target));
+        menu.append_item(&item);
+    }
+    let item = gio::MenuItem::new(Some("View unit file"), None);
+    item.set_action_and_target_value(Some("win.unit-view"), Some(&target));
+    menu.append_item(&item);
+    let pin_label = if unit_object.favorite() {
+        "Unpin"
     } else {
-        // removal is necessary because of widget reuse
-        WidgetExt::remove_css_class(&boxx, "inactive");
+        "Pin to Top"
+    };
+    let item = gio::MenuItem::new(Some(pin_label), None);
+    item.set_action_and_target_value(Some("win.unit-pin"), Some(&target));
+    menu.append_item(&item);
+    let watch_label = if unit_object.watched() {
+        "Stop Watching"
+    } else {
+        "Watch Resources"
+    };
+    let item = gio::MenuItem::new(Some(watch_label), None);
+    item.set_action_and_target_value(Some("win.unit-watch"), Some(&target));
+    menu.append_item(&item);
+
+    let popover = PopoverMenu::from_model(Some(&menu));
+    popover.set_parent(parent);
+    popover.set_has_arrow(false);
+    popover.set_pointing_to(Some(&Rectangle::new(x as i32, y as i32, 1, 1)));
+    popover.connect_closed(|popover| {
+        // unparent once the popover is gone, it must not be unparented while closing
+        let popover = popover.clone();
+        glib::idle_add_local_once(move || popover.unparent());
+    });
+    popover.popup();
+}
+
+fn build_label(list_item: &Object, transform_fn: &Getter, highlighter: Option<&Highlighter>) {
+    let list_item = list_item
+        .downcast_ref::<ListItem>()
+        .expect("Needs to be ListItem");
+    let item = list_item.item().expect("The item has to be set.");
+
+    let boxx = match list_item.child().and_downcast::<TreeExpander>() {
+        Some(expander) => {
+            expander.set_list_row(item.downcast_ref::<TreeListRow>());
+            expander.child()
+        }
+        None => list_item.child(),
     }
+    .and_downcast::<gtk::Box>()
+    .expect("The child has to be a `Box`.");
+
+    let Some(unit_object) = unit_object_of(&item) else {
+        // only the unit column shows the summary of a group
+        boxx.set_tooltip_text(None);
+        set_state_classes(&boxx, None, None);
+        show_group_summary(&boxx, true);
+        return;
+    };
+    show_group_summary(&boxx, false);
 
-    let label = boxx
-        .first_child()
-        .unwrap()
-        .downcast::<Label>()
-        .expect("The child has to be a `Label`.");
+    boxx.set_tooltip_text(Some(unit_object.unit_name().as_str()));
+
+    let state = State::from_str(&unit_object.state()).ok();
+    let load_state = LoadState::from_str(&unit_object.load()).ok();
+    set_state_classes(&boxx, state, load_state);
+
+    let state_icon = boxx.first_child().and_downcast::<Image>();
+    let label = match &state_icon {
+        Some(state_icon) => state_icon.next_sibling(),
+        None => boxx.first_child(),
+    }
+    .and_downcast::<Label>()
+    .expect("The child has to be a `Label`.");
+    if let Some(state_icon) = state_icon {
+        let load_icon = load_state.and_then(|load_state| load_state.icon_name());
+        state_icon.set_icon_name(load_icon.or(state.map(|state| state.icon_name())));
+        state_icon.set_tooltip_text(Some(&format!(
+            "{} · {}",
+            unit_object.load(),
+            unit_object.state()
+        )));
+    }
 
     let label_text = transform_fn(&unit_object);
-    label.set_label(&label_text);
+    let positions = highlighter.map_or_else(Vec::new, |highlighter| highlighter(&label_text));
+    label.set_markup(&highlight_markup(&label_text, &positions));
+}
+
+/// Styles the cell for the state and load state of its unit, removing the classes of the unit it showed before.
+fn set_state_classes(boxx: &gtk::Box, state: Option<State>, load_state: Option<LoadState>) {
+    let current = state
+        .map(|state| state.css_class())
+        .into_iter()
+        .chain(
+            load_state
+                .and_then(|load_state| load_state.css_class())
+                .map(String::from),
+        )
+        .collect::<Vec<_>>();
+    // removal is necessary because of widget reuse
+    for class in boxx.css_classes() {
+        let is_state_class = class.starts_with("state-") || class.starts_with("load-");
+        if is_state_class && !current.iter().any(|current| *current == class) {
+            WidgetExt::remove_css_class(boxx, &class);
+        }
+    }
+    for class in current {
+        WidgetExt::add_css_class(boxx, &class);
+    }
+}
+
+/// Builds the legend of the row styles and icons, one row per state and styled load state.
+pub fn build_legend() -> gtk::Box {
+    let legend = gtk::Box::new(Orientation::Vertical, 6);
+    let add_row = |icon_name: &str, text: &str, css_class: &str| {
+        let row = gtk::Box::new(Orientation::Horizontal, 6);
+        row.add_css_class(css_class);
+        row.append(&Image::from_icon_name(icon_name));
+        row.append(&Label::new(Some(text)));
+        legend.append(&row);
+    };
+    for state in State::iter() {
+        add_row(state.icon_name(), &state.to_string(), &state.css_class());
+    }
+    for load_state in LoadState::STYLED {
+        if let (Some(icon_name), Some(css_class)) = (load_state.icon_name(), load_state.css_class())
+        {
+            add_row(icon_name, &load_state.to_string(), css_class);
+        }
+    }
+    legend
+}
+
+/// Shows either the summary of a group or the widgets of a unit, as the widgets are reused for both.
+///
+/// The indicators of a unit are left to their bindings, see `bind_indicators`.
+fn show_group_summary(boxx: &gtk::Box, group: bool) {
+    let mut child = boxx.first_child();
+    while let Some(widget) = child {
+        if group || !widget.has_css_class(INDICATOR_CLASS) {
+            widget.set_visible(widget.has_css_class(GROUP_SUMMARY_CLASS) == group);
+        }
+        child = widget.next_sibling();
+    }
+}
+
+/// Escapes the text for Pango markup and makes the characters at the given char positions bold.
+fn highlight_markup(text: &str, positions: &[usize]) -> String {
+    let mut markup = String::with_capacity(text.len());
+    let mut highlighted = false;
+    for (position, c) in text.chars().enumerate() {
+        let highlight = positions.binary_search(&position).is_ok();
+        if highlight != highlighted {
+            markup.push_str(if highlight { "<b>" } else { "</b>" });
+            highlighted = highlight;
+        }
+        markup.push_str(&glib::markup_escape_text(c.encode_utf8(&mut [0; 4])));
+    }
+    if highlighted {
+        markup.push_str("</b>");
+    }
+    markup
 }
 
 fn with_expand(
     unit_name: &str,
     factory: SignalListItemFactory,
-    getter: fn(&UnitObject) -> String,
+    getter: Getter,
     split_func: SplitFunction,
 ) -> ColumnViewColumn {
     let column = ColumnViewColumn::new(Some(unit_name), Some(factory.upcast::<ListItemFactory>()));
@@ -128,7 +744,11 @@ fn with_expand(
                 other => other.into(),
             }
         } else {
-            string_compare_sort(value_1, value_2)
+            match (value_1.parse::<u64>(), value_2.parse::<u64>()) {
+                // numeric properties like `MainPID` or `MemoryCurrent`
+                (Ok(number_1), Ok(number_2)) => number_1.cmp(&number_2).into(),
+                _ => string_compare_sort(value_1, value_2),
+            }
         }
     });
     column.set_sorter(Some(&sorter));
@@ -138,12 +758,3 @@ fn with_expand(
 fn string_compare_sort(value_1: String, value_2: String) -> Ordering {
     value_1.to_lowercase().cmp(&value_2.to_lowercase()).into()
 }
-
-/// Split unit name and suffix in order to enable two layer sorting
-fn split_name_and_suffix(s: &str) -> (&str, &str) {
-    if let Some(idx) = s.rfind('.') {
-        (&s[..idx], &s[idx..])
-    } else {
-        (s, "")
-    }
-}
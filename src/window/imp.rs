@@ -1,10 +1,20 @@
+use crate::cgroups::CgroupBrowser;
+use crate::detail::UnitDetail;
+use crate::history::HistoryEntry;
+use crate::metrics::{Histories, Status};
+use crate::monitor::ResourceMonitor;
 use crate::systemd::unit::UnitObject;
-use adw::gio::ListStore;
+use adw::gio::{ApplicationHoldGuard, ListStore};
 use adw::glib::subclass::InitializingObject;
+use adw::prelude::StaticTypeExt;
 use adw::subclass::prelude::*;
-use adw::{glib, Dialog, HeaderBar, ToastOverlay};
-use gtk::{ActionBar, Button, ColumnView, CompositeTemplate, SearchBar, SearchEntry, TextView};
-use std::cell::RefCell;
+use adw::{gio, glib, Banner, Dialog, EntryRow, OverlaySplitView, ToastOverlay, ViewStack};
+use gtk::{
+    ActionBar, Button, ColumnView, CompositeTemplate, Label, ListBox, MenuButton, SearchBar,
+    SearchEntry, SortListModel,
+};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::{HashMap, HashSet};
 
 // Object holding the state
 #[derive(CompositeTemplate, Default)]
@@ -22,11 +32,38 @@ pub struct Window {
     #[template_child]
     pub search_filter: TemplateChild<SearchEntry>,
 
+    #[template_child]
+    pub search_error: TemplateChild<Label>,
+
+    #[template_child]
+    pub chip_box: TemplateChild<gtk::Box>,
+
+    #[template_child]
+    pub saved_filters_button: TemplateChild<MenuButton>,
+
     #[template_child]
     pub bottom_bar: TemplateChild<ActionBar>,
 
     #[template_child]
-    pub dialog: TemplateChild<Dialog>,
+    pub detail_split_view: TemplateChild<OverlaySplitView>,
+
+    #[template_child]
+    pub unit_detail: TemplateChild<UnitDetail>,
+
+    #[template_child]
+    pub page_stack: TemplateChild<ViewStack>,
+
+    #[template_child]
+    pub cgroup_browser: TemplateChild<CgroupBrowser>,
+
+    #[template_child]
+    pub chip_bar: TemplateChild<gtk::ScrolledWindow>,
+
+    #[template_child]
+    pub health_banner: TemplateChild<Banner>,
+
+    #[template_child]
+    pub legend_button: TemplateChild<MenuButton>,
 
     #[template_child]
     pub start_button: TemplateChild<Button>,
@@ -47,12 +84,62 @@ pub struct Window {
     pub view_unit_button: TemplateChild<Button>,
 
     #[template_child]
-    pub text_view: TemplateChild<TextView>,
+    pub history_split_view: TemplateChild<OverlaySplitView>,
+
+    #[template_child]
+    pub history_list: TemplateChild<ListBox>,
+
+    #[template_child]
+    pub columns_dialog: TemplateChild<Dialog>,
 
     #[template_child]
-    pub file_header_bar: TemplateChild<HeaderBar>,
+    pub columns_list: TemplateChild<ListBox>,
+
+    #[template_child]
+    pub column_property_entry: TemplateChild<EntryRow>,
+
+    #[template_child]
+    pub audit_dialog: TemplateChild<Dialog>,
+
+    #[template_child]
+    pub audit_list: TemplateChild<ListBox>,
+
+    #[template_child]
+    pub audit_banner: TemplateChild<Banner>,
+
+    pub settings: OnceCell<gio::Settings>,
 
     pub list_store: RefCell<Option<ListStore>>,
+
+    /// The filtered and sorted units, shown as they are or in groups.
+    pub sort_model: RefCell<Option<SortListModel>>,
+
+    /// The names of the groups currently shown, empty if the units are not grouped.
+    pub group_keys: RefCell<Vec<String>>,
+
+    /// Actions taken in this session, newest first.
+    pub history_store: RefCell<Option<ListStore>>,
+
+    /// Unit to select once the units are loaded.
+    pub pending_selection: RefCell<Option<String>>,
+
+    /// The state of each watched unit at the previous sample, to notify about changes.
+    pub watched_statuses: RefCell<HashMap<String, Status>>,
+
+    /// Whether the system state is being read, so checks do not pile up.
+    pub checking_health: Cell<bool>,
+
+    /// The resource usage histories of the watched units, drawn by the resource graphs of the unit detail.
+    pub histories: Histories,
+
+    /// Whether the watched units are being sampled, so samples do not pile up.
+    pub sampling: Cell<bool>,
+
+    /// Names of the units with a job of this app running, see `Window::set_busy`.
+    pub busy_units: RefCell<HashSet<String>>,
+
+    /// Keeps the app running while the window is hidden in the background.
+    pub background_hold: RefCell<Option<ApplicationHoldGuard>>,
 }
 
 // The central trait for subclassing a GObject
@@ -64,6 +151,9 @@ impl ObjectSubclass for Window {
     type ParentType = adw::ApplicationWindow;
 
     fn class_init(klass: &mut Self::Class) {
+        UnitDetail::ensure_type();
+        CgroupBrowser::ensure_type();
+        ResourceMonitor::ensure_type();
         klass.bind_template();
     }
 
@@ -81,8 +171,18 @@ impl ObjectImpl for Window {
         // Setup
         self.list_store
             .replace(Some(ListStore::new::<UnitObject>()));
+        self.history_store
+            .replace(Some(ListStore::new::<HistoryEntry>()));
         let obj = self.obj();
+        obj.setup_settings();
         obj.setup_column_view();
+        obj.setup_grouping();
+        obj.setup_saved_filters();
+        obj.setup_columns_dialog();
+        obj.setup_history();
+        obj.setup_pages();
+        obj.setup_legend();
+        obj.setup_detail_pane();
         obj.setup_actions();
     }
 }
@@ -90,7 +190,14 @@ impl ObjectImpl for Window {
 // Trait shared by all widgets
 impl WidgetImpl for Window {}
 
-impl WindowImpl for Window {}
+impl WindowImpl for Window {
+    fn close_request(&self) -> glib::Propagation {
+        if self.obj().hide_to_background() {
+            return glib::Propagation::Stop;
+        }
+        self.parent_close_request()
+    }
+}
 
 // Trait shared by all application windows
 impl ApplicationWindowImpl for Window {}
@@ -1,22 +1,55 @@
 mod imp;
 
-use crate::systemd::{unit::UnitObject, SystemCtrlAction};
-use crate::{systemd, table};
+use crate::audit::AuditEntry;
+use crate::export::ExportFormat;
+use crate::filter::{SavedFilter, UnitFilter, SAVED_FILTERS_KEY, STATES, UNIT_TYPES};
+use crate::group::{GroupObject, Grouping, GROUPING_KEY, SLICE_PROPERTY};
+use crate::history::HistoryEntry;
+use crate::metrics::{
+    Sample, Status, StatusChange, SAMPLED_PROPERTIES, SAMPLE_INTERVAL, STATUS_PROPERTIES,
+    WATCHED_UNITS_KEY,
+};
+use crate::query::Query;
+use crate::systemd::{unit::UnitObject, JobResult, SystemCtrlAction};
+use crate::{audit, history, settings, systemd, table};
 use adw::gio::{ActionEntry, ListStore};
-use adw::glib::{clone, Object};
-use adw::prelude::{ActionMapExtManual, AdwDialogExt, Cast};
+use adw::glib::{clone, ControlFlow, Object};
+use adw::prelude::{
+    ActionMapExt, ActionMapExtManual, ActionRowExt, ActionableExt, AdwDialogExt, AlertDialogExt,
+    AlertDialogExtManual, ApplicationExt, ApplicationExtManual, Cast, CastNone, EntryRowExt,
+    FileExt, FromVariant, GtkWindowExt, IsA, ListModelExt, ListModelExtManual, ObjectExt,
+    PreferencesRowExt, SettingsExt, SettingsExtManual, StaticVariantType, ToVariant,
+};
 use adw::subclass::prelude::ObjectSubclassIsExt;
-use adw::{gio, glib, Toast, ToastOverlay};
+use adw::{gio, glib, ActionRow, AlertDialog, ResponseAppearance, Toast, ToastOverlay};
 use async_channel::{Receiver, Sender};
-use gtk::prelude::{ButtonExt, EditableExt, FilterExt, SelectionModelExt, TextViewExt, WidgetExt};
+use gtk::prelude::{
+    AdjustmentExt, BoxExt, ButtonExt, CheckButtonExt, EditableExt, EntryExt, FilterExt,
+    ScrollableExt, SelectionModelExt, SorterExt, ToggleButtonExt, WidgetExt,
+};
 use gtk::{
-    Button, CustomFilter, FilterChange, FilterListModel, SingleSelection, SortListModel, TextBuffer,
+    Align, Button, CheckButton, ColumnViewColumn, CustomFilter, CustomSorter, Entry, FileDialog,
+    FileFilter, FilterChange, FilterListModel, Label, MultiSelection, MultiSorter, Orientation,
+    Popover, Separator, SortListModel, SorterChange, Switch, ToggleButton, TreeListModel,
+    TreeListRow,
 };
 use std::cell::RefCell;
-use std::fmt::Write;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
 use std::future::Future;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::time::Instant;
+use strum::IntoEnumIterator;
+
+/// Settings key of the units pinned to the top of the table.
+const FAVORITE_UNITS_KEY: &str = "favorite-units";
+
+/// Settings key of the units whose destructive actions run without asking for confirmation.
+const CONFIRM_SKIP_UNITS_KEY: &str = "confirm-skip-units";
+
+/// Settings key of whether closing the window keeps the app running in the background.
+const RUN_IN_BACKGROUND_KEY: &str = "run-in-background";
 
 glib::wrapper! {
     pub struct Window(ObjectSubclass<imp::Window>)
@@ -31,32 +64,105 @@ impl Window {
         Object::builder().property("application", app).build()
     }
 
+    fn setup_settings(&self) {
+        let settings = settings::app_settings();
+        self.imp()
+            .settings
+            .set(settings)
+            .expect("`settings` should not be set before calling `setup_settings`.");
+    }
+
+    fn settings(&self) -> &gio::Settings {
+        self.imp()
+            .settings
+            .get()
+            .expect("`settings` should be set in `setup_settings`.")
+    }
+
     fn setup_column_view(&self) {
-        let (units_receiver, toast_text_receiver) = Self::start_update();
+        let (units_receiver, toast_text_receiver) = Self::start_update(self.unit_properties());
 
         let model = self.imp().list_store.clone().into_inner().unwrap();
-        let filter_input_value: Rc<RefCell<String>> = Rc::new(RefCell::new(String::new()));
+        let unit_filter: Rc<RefCell<UnitFilter>> = Rc::new(RefCell::new(UnitFilter::default()));
 
         // Clone Rc for the filter closure
-        let filter_input_lower_case = Rc::clone(&filter_input_value);
-        let filter =
-            CustomFilter::new(move |obj| Self::search_filter(&filter_input_lower_case, obj));
+        let filter_unit_filter = Rc::clone(&unit_filter);
+        let filter = CustomFilter::new(move |obj| Self::search_filter(&filter_unit_filter, obj));
 
-        // Now clone the Rc for the search_changed callback
-        self.build_search_filter(filter.clone(), Rc::clone(&filter_input_value));
+        // While searching, the most relevant units come first, the column sorter applies to equally relevant ones
+        let relevance_unit_filter = Rc::clone(&unit_filter);
+        let relevance_sorter = CustomSorter::new(move |one, two| {
+            Self::relevance_sort(&relevance_unit_filter, one, two)
+        });
+
+        // Now clone the Rc for the search_changed callback and the chips
+        self.build_search_filter(
+            filter.clone(),
+            relevance_sorter.clone(),
+            Rc::clone(&unit_filter),
+        );
+        self.build_filter_chips(filter.clone(), Rc::clone(&unit_filter));
 
         // Now create the FilterListModel using the filter
         let filter_model = FilterListModel::new(Some(model.clone()), Some(filter.clone()));
 
+        // Favorite units stay on top, see `setup_favorites`
+        let favorite_sorter = CustomSorter::new(|one, two| {
+            let favorite = |obj: &Object| {
+                obj.downcast_ref::<UnitObject>()
+                    .expect("The object needs to be of type `UnitObject`.")
+                    .favorite()
+            };
+            favorite(two).cmp(&favorite(one)).into()
+        });
+
         let column_view = self.imp().column_view.get();
-        let sort_model = SortListModel::new(Some(filter_model), column_view.sorter());
+        let sorter = MultiSorter::new();
+        sorter.append(favorite_sorter.clone());
+        sorter.append(relevance_sorter);
+        if let Some(column_sorter) = column_view.sorter() {
+            sorter.append(column_sorter);
+        }
+        let sort_model = SortListModel::new(Some(filter_model), Some(sorter));
+
+        self.imp().sort_model.replace(Some(sort_model));
+        self.apply_grouping();
+        table::setup_columns(&column_view, &filter, &unit_filter, self.settings());
 
-        let single_selection = SingleSelection::new(Some(sort_model));
-        single_selection.set_autoselect(false);
-        self.connect_selection_changed(&single_selection);
+        self.setup_favorites(&model, &favorite_sorter);
+        self.setup_busy_units(&model);
+        self.setup_watched_units(&model);
+        self.setup_health_banner(&model);
 
-        column_view.set_model(Some(&single_selection));
-        table::setup_columns(&column_view);
+        // the groups change along with the loaded units
+        model.connect_items_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |model, _, _, _| {
+                // skip the units being removed before the reloaded ones are added
+                let grouping = window.grouping();
+                if grouping == Grouping::None || model.n_items() == 0 {
+                    return;
+                }
+                if Self::group_keys(model, grouping) != *window.imp().group_keys.borrow() {
+                    window.apply_grouping();
+                }
+            }
+        ));
+
+        // select the unit requested before the units were loaded, see `open_unit`
+        model.connect_items_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, _, _, _| {
+                let pending_selection = window.imp().pending_selection.borrow().clone();
+                if let Some(unit_name) = pending_selection {
+                    if window.select_unit(&unit_name) {
+                        window.imp().pending_selection.replace(None);
+                    }
+                }
+            }
+        ));
 
         Self::await_update(
             self.imp().overlay.clone(),
@@ -66,8 +172,790 @@ impl Window {
         );
     }
 
-    fn start_await_update(model: ListStore, overlay: ToastOverlay) {
-        let (units_receiver, toast_text_receiver) = Self::start_update();
+    fn grouping(&self) -> Grouping {
+        Grouping::from_str(&self.settings().string(GROUPING_KEY)).unwrap_or(Grouping::None)
+    }
+
+    fn setup_grouping(&self) {
+        self.settings().connect_changed(
+            Some(GROUPING_KEY),
+            clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, _| {
+                    if window.grouping() == Grouping::Slice {
+                        window.load_unit_property(SLICE_PROPERTY.to_string(), |window| {
+                            window.apply_grouping()
+                        });
+                    } else {
+                        window.apply_grouping();
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Backs the table by the flat list of units or by a tree of groups of units, keeping expanded groups expanded.
+    fn apply_grouping(&self) {
+        let sort_model = self
+            .imp()
+            .sort_model
+            .borrow()
+            .clone()
+            .expect("The sort model needs to be set in `setup_column_view`.");
+        let expanded_groups = self.expanded_groups();
+        let model = match self.grouping() {
+            Grouping::None => {
+                self.imp().group_keys.replace(vec![]);
+                sort_model.upcast::<gio::ListModel>()
+            }
+            grouping => self.build_group_tree(grouping, &sort_model).upcast(),
+        };
+
+        let multi_selection = MultiSelection::new(Some(model.clone()));
+        self.connect_selection_changed(&multi_selection);
+        self.imp().column_view.set_model(Some(&multi_selection));
+
+        if let Some(tree) = model.downcast_ref::<TreeListModel>() {
+            // expanding a group inserts its units after it, which are skipped
+            let mut position = 0;
+            while let Some(row) = tree.row(position) {
+                let expanded = row
+                    .item()
+                    .and_downcast::<GroupObject>()
+                    .is_some_and(|group| expanded_groups.contains(&group.name()));
+                if expanded {
+                    row.set_expanded(true);
+                }
+                position += 1;
+            }
+        }
+    }
+
+    /// Groups the filtered and sorted units, hiding groups without matching units.
+    fn build_group_tree(&self, grouping: Grouping, sort_model: &SortListModel) -> TreeListModel {
+        let model = self.imp().list_store.clone().into_inner().unwrap();
+        let group_keys = Self::group_keys(&model, grouping);
+
+        let groups = ListStore::new::<GroupObject>();
+        let group_filter = CustomFilter::new(|obj| {
+            obj.downcast_ref::<GroupObject>()
+                .is_some_and(|group| group.count() > 0)
+        });
+        for group_key in &group_keys {
+            let group = GroupObject::new(group_key, grouping, sort_model);
+            group.connect_count_notify(clone!(
+                #[weak]
+                group_filter,
+                move |_| group_filter.changed(FilterChange::Different)
+            ));
+            groups.append(&group);
+        }
+        self.imp().group_keys.replace(group_keys);
+
+        let root = FilterListModel::new(Some(groups), Some(group_filter));
+        TreeListModel::new(root, false, false, |item| {
+            item.downcast_ref::<GroupObject>()
+                .map(|group| group.units().upcast())
+        })
+    }
+
+    /// The sorted names of the groups of the units.
+    fn group_keys(model: &ListStore, grouping: Grouping) -> Vec<String> {
+        model
+            .iter::<UnitObject>()
+            .flatten()
+            .filter_map(|unit_object| grouping.key(&unit_object))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn expanded_groups(&self) -> HashSet<String> {
+        let Some(tree) = self.group_tree() else {
+            return HashSet::new();
+        };
+        (0..tree.n_items())
+            .filter_map(|position| tree.row(position))
+            .filter(TreeListRow::is_expanded)
+            .filter_map(|row| row.item().and_downcast::<GroupObject>())
+            .map(|group| group.name())
+            .collect()
+    }
+
+    /// The tree backing the table if the units are grouped.
+    fn group_tree(&self) -> Option<TreeListModel> {
+        self.imp()
+            .column_view
+            .model()
+            .and_downcast::<MultiSelection>()
+            .and_then(|selection| selection.model())
+            .and_downcast::<TreeListModel>()
+    }
+
+    /// Marks loaded units as favorites and keeps them in sync with the settings.
+    fn setup_favorites(&self, model: &ListStore, favorite_sorter: &CustomSorter) {
+        model.connect_items_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |model, position, _, added| {
+                let favorites = window.settings().strv(FAVORITE_UNITS_KEY);
+                for unit_object in (position..position + added)
+                    .filter_map(|position| model.item(position).and_downcast::<UnitObject>())
+                {
+                    unit_object.set_favorite(favorites.contains(unit_object.unit_name()));
+                }
+            }
+        ));
+
+        self.settings().connect_changed(
+            Some(FAVORITE_UNITS_KEY),
+            clone!(
+                #[weak]
+                model,
+                #[weak]
+                favorite_sorter,
+                move |settings, key| {
+                    let favorites = settings.strv(key);
+                    for unit_object in model.iter::<UnitObject>().flatten() {
+                        unit_object.set_favorite(favorites.contains(unit_object.unit_name()));
+                    }
+                    // move the rows to or from the top, the star is bound to `favorite`
+                    favorite_sorter.changed(SorterChange::Different);
+                }
+            ),
+        );
+    }
+
+    fn toggle_favorite(&self, unit_name: &str) {
+        if let Err(error) = self.toggle_unit_setting(FAVORITE_UNITS_KEY, unit_name) {
+            self.imp()
+                .overlay
+                .add_toast(Toast::new(&format!("Failed to pin {unit_name}: {error}")));
+        }
+    }
+
+    fn toggle_watched(&self, unit_name: &str) {
+        if let Err(error) = self.toggle_unit_setting(WATCHED_UNITS_KEY, unit_name) {
+            self.imp()
+                .overlay
+                .add_toast(Toast::new(&format!("Failed to watch {unit_name}: {error}")));
+        }
+    }
+
+    /// Adds the unit to or removes it from a list of unit names in the settings.
+    fn toggle_unit_setting(&self, key: &str, unit_name: &str) -> Result<(), glib::BoolError> {
+        let mut unit_names = self
+            .settings()
+            .strv(key)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        match unit_names.iter().position(|name| name == unit_name) {
+            Some(index) => {
+                unit_names.remove(index);
+            }
+            None => unit_names.push(unit_name.to_string()),
+        }
+        self.settings().set_strv(key, unit_names)
+    }
+
+    /// Marks units loaded while a job of this app runs for them as busy, see `set_busy`.
+    fn setup_busy_units(&self, model: &ListStore) {
+        model.connect_items_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |model, position, _, added| {
+                let busy_units = window.imp().busy_units.borrow();
+                for unit_object in (position..position + added)
+                    .filter_map(|position| model.item(position).and_downcast::<UnitObject>())
+                {
+                    unit_object.set_busy(busy_units.contains(&unit_object.unit_name()));
+                }
+            }
+        ));
+    }
+
+    /// Marks loaded units as watched and samples the resource usage of the watched units while the app is open.
+    fn setup_watched_units(&self, model: &ListStore) {
+        self.imp()
+            .unit_detail
+            .set_resource_histories(Rc::clone(&self.imp().histories));
+        model.connect_items_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |model, position, _, added| {
+                let watched = window.settings().strv(WATCHED_UNITS_KEY);
+                for unit_object in (position..position + added)
+                    .filter_map(|position| model.item(position).and_downcast::<UnitObject>())
+                {
+                    unit_object.set_watched(watched.contains(unit_object.unit_name()));
+                }
+            }
+        ));
+
+        self.settings().connect_changed(
+            Some(WATCHED_UNITS_KEY),
+            clone!(
+                #[weak]
+                model,
+                move |settings, key| {
+                    let watched = settings.strv(key);
+                    for unit_object in model.iter::<UnitObject>().flatten() {
+                        unit_object.set_watched(watched.contains(unit_object.unit_name()));
+                    }
+                }
+            ),
+        );
+
+        glib::timeout_add_seconds_local(
+            SAMPLE_INTERVAL,
+            clone!(
+                #[weak(rename_to = window)]
+                self,
+                #[upgrade_or]
+                ControlFlow::Break,
+                move || {
+                    window.sample_watched_units();
+                    ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    fn sample_watched_units(&self) {
+        let unit_names = self
+            .settings()
+            .strv(WATCHED_UNITS_KEY)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        let imp = self.imp();
+        // forget the samples of units no longer watched
+        imp.histories
+            .borrow_mut()
+            .retain(|unit_name, _| unit_names.contains(unit_name));
+        imp.watched_statuses
+            .borrow_mut()
+            .retain(|unit_name, _| unit_names.contains(unit_name));
+        if unit_names.is_empty() || imp.sampling.replace(true) {
+            return;
+        }
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let sampled_unit_names = unit_names.clone();
+                let values = gio::spawn_blocking(move || {
+                    let properties = SAMPLED_PROPERTIES
+                        .iter()
+                        .chain(&STATUS_PROPERTIES)
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>();
+                    systemd::show_properties(&sampled_unit_names, &properties)
+                })
+                .await
+                .expect("The lookup needs to finish.");
+                window.imp().sampling.set(false);
+                let values = match values {
+                    Ok(values) => values,
+                    Err(error) => {
+                        toast(
+                            &window,
+                            &format!("Could not sample the watched units: {error}"),
+                        );
+                        return;
+                    }
+                };
+                for (unit_name, values) in unit_names.into_iter().zip(values) {
+                    let status = Status::new(&values);
+                    let previous = window
+                        .imp()
+                        .watched_statuses
+                        .borrow_mut()
+                        .insert(unit_name.clone(), status.clone());
+                    // changes caused by the jobs of this app are expected, see `run_action`
+                    let busy = window.imp().busy_units.borrow().contains(&unit_name);
+                    if let Some(change) = previous
+                        .filter(|_| !busy)
+                        .and_then(|previous| status.change_since(&previous))
+                    {
+                        window.notify_status_change(&unit_name, &change);
+                    }
+                    window
+                        .imp()
+                        .histories
+                        .borrow_mut()
+                        .entry(unit_name)
+                        .or_default()
+                        .push(Sample::new(&values));
+                }
+                window.imp().unit_detail.update_resource_graphs();
+            }
+        ));
+    }
+
+    /// Shows a banner while the system is not running normally, checked after the units are loaded and then
+    /// periodically, as units fail or recover without a reload.
+    fn setup_health_banner(&self, model: &ListStore) {
+        model.connect_items_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |model, _, _, added| {
+                // only check after the units were (re)loaded, not whenever a single row changes
+                if added > 0 && added == model.n_items() {
+                    window.update_health_banner();
+                }
+            }
+        ));
+        glib::timeout_add_seconds_local(
+            SAMPLE_INTERVAL,
+            clone!(
+                #[weak(rename_to = window)]
+                self,
+                #[upgrade_or]
+                ControlFlow::Break,
+                move || {
+                    window.update_health_banner();
+                    ControlFlow::Continue
+                }
+            ),
+        );
+        self.imp().health_banner.connect_button_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.show_failed_units()
+        ));
+    }
+
+    fn update_health_banner(&self) {
+        let imp = self.imp();
+        if imp.checking_health.replace(true) {
+            return;
+        }
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let system_state = gio::spawn_blocking(systemd::system_state)
+                    .await
+                    .expect("The lookup needs to finish.");
+                window.imp().checking_health.set(false);
+                // keep the banner as it is if the state is unknown
+                let Ok(system_state) = system_state else {
+                    return;
+                };
+                let failed = system_state.failed_units;
+                let headline = match system_state.state.as_str() {
+                    "degraded" => "The system is degraded",
+                    "maintenance" => "The system is in maintenance mode",
+                    "starting" => "The system is starting up",
+                    _ => {
+                        window.imp().health_banner.set_revealed(false);
+                        return;
+                    }
+                };
+                let banner = &window.imp().health_banner;
+                banner.set_title(&match failed {
+                    0 => headline.to_string(),
+                    1 => format!("{headline}: 1 unit failed"),
+                    failed => format!("{headline}: {failed} units failed"),
+                });
+                banner.set_button_label((failed > 0).then_some("Show Failed Units"));
+                banner.set_revealed(true);
+            }
+        ));
+    }
+
+    /// Filters the unit table to the failed units only.
+    fn show_failed_units(&self) {
+        for chip in self.chips() {
+            chip.set_active(chip.label().is_some_and(|label| label == "failed"));
+        }
+        let imp = self.imp();
+        imp.search_filter.set_text("");
+        imp.page_stack.set_visible_child_name("units");
+    }
+
+    /// Sends a desktop notification offering to show the logs or to restart the unit.
+    ///
+    /// The buttons activate `app.unit-logs` and `app.unit-restart`, which also work once the window is hidden.
+    fn notify_status_change(&self, unit_name: &str, change: &StatusChange) {
+        let Some(app) = self.application() else {
+            return;
+        };
+        let target = unit_name.to_variant();
+        let notification = gio::Notification::new(&change.title(unit_name));
+        notification.set_body(Some(&change.body()));
+        notification.set_default_action_and_target_value("app.unit-logs", Some(&target));
+        notification.add_button_with_target_value("Show Logs", "app.unit-logs", Some(&target));
+        notification.add_button_with_target_value("Restart", "app.unit-restart", Some(&target));
+        app.send_notification(Some(&format!("unit-{unit_name}")), &notification);
+    }
+
+    /// Hides the window instead of closing it if the app runs in the background, so watched units are still sampled.
+    ///
+    /// Returns whether the window was hidden.
+    pub fn hide_to_background(&self) -> bool {
+        let Some(app) = self.application() else {
+            return false;
+        };
+        if !self.settings().boolean(RUN_IN_BACKGROUND_KEY) {
+            return false;
+        }
+        self.set_visible(false);
+        self.imp().background_hold.replace(Some(app.hold()));
+        true
+    }
+
+    /// Presents the window again after it was hidden in the background.
+    pub fn present_from_background(&self) {
+        self.imp().background_hold.take();
+        GtkWindowExt::present(self);
+    }
+
+    fn setup_saved_filters(&self) {
+        self.update_saved_filters_menu();
+        self.settings().connect_changed(
+            Some(SAVED_FILTERS_KEY),
+            clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, _| window.update_saved_filters_menu()
+            ),
+        );
+    }
+
+    fn update_saved_filters_menu(&self) {
+        let saved_filters = SavedFilter::load(self.settings());
+        let filters_section = gio::Menu::new();
+        let delete_menu = gio::Menu::new();
+        for saved_filter in &saved_filters {
+            let target = saved_filter.name.to_variant();
+            let item = gio::MenuItem::new(Some(&saved_filter.name), None);
+            item.set_action_and_target_value(Some("win.filter-apply"), Some(&target));
+            filters_section.append_item(&item);
+            let item = gio::MenuItem::new(Some(&saved_filter.name), None);
+            item.set_action_and_target_value(Some("win.filter-delete"), Some(&target));
+            delete_menu.append_item(&item);
+        }
+
+        let menu = gio::Menu::new();
+        menu.append_section(None, &filters_section);
+        let actions_section = gio::Menu::new();
+        actions_section.append(Some("Save Current Filter…"), Some("win.filter-save"));
+        if !saved_filters.is_empty() {
+            actions_section.append_submenu(Some("Delete Filter"), &delete_menu);
+        }
+        menu.append_section(None, &actions_section);
+        self.imp().saved_filters_button.set_menu_model(Some(&menu));
+    }
+
+    /// The labels of the toggled chips.
+    fn active_chips(&self) -> Vec<String> {
+        self.chips()
+            .filter(|chip| chip.is_active())
+            .filter_map(|chip| chip.label())
+            .map(|label| label.to_string())
+            .collect()
+    }
+
+    fn chips(&self) -> impl Iterator<Item = ToggleButton> {
+        let mut child = self.imp().chip_box.first_child();
+        std::iter::from_fn(move || {
+            let current = child.take()?;
+            child = current.next_sibling();
+            Some(current)
+        })
+        .filter_map(|widget| widget.downcast::<ToggleButton>().ok())
+    }
+
+    fn save_filter(&self) {
+        let query = self.imp().search_filter.text().to_string();
+        let chips = self.active_chips();
+        if query.trim().is_empty() && chips.is_empty() {
+            self.imp()
+                .overlay
+                .add_toast(Toast::new("Type a query or toggle chips to save a filter"));
+            return;
+        }
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let dialog = AlertDialog::new(Some("Save Filter"), None);
+                dialog.add_responses(&[("cancel", "Cancel"), ("save", "Save")]);
+                dialog.set_response_appearance("save", ResponseAppearance::Suggested);
+                dialog.set_default_response(Some("save"));
+                dialog.set_close_response("cancel");
+                dialog.set_response_enabled("save", false);
+
+                let name_entry = Entry::new();
+                name_entry.set_placeholder_text(Some("Name"));
+                name_entry.set_activates_default(true);
+                name_entry.connect_changed(clone!(
+                    #[weak]
+                    dialog,
+                    move |entry| {
+                        dialog.set_response_enabled("save", !entry.text().trim().is_empty());
+                    }
+                ));
+                dialog.set_extra_child(Some(&name_entry));
+
+                if dialog.choose_future(&window).await != "save" {
+                    return;
+                }
+                let saved_filter = SavedFilter {
+                    name: name_entry.text().trim().to_string(),
+                    query,
+                    chips,
+                };
+                let toast_text = match saved_filter.save(window.settings()) {
+                    Ok(()) => format!("Saved filter {}", saved_filter.name),
+                    Err(error) => format!("Failed to save filter {}: {error}", saved_filter.name),
+                };
+                window.imp().overlay.add_toast(Toast::new(&toast_text));
+            }
+        ));
+    }
+
+    fn apply_filter(&self, name: &str) {
+        let saved_filters = SavedFilter::load(self.settings());
+        let Some(saved_filter) = saved_filters
+            .iter()
+            .find(|saved_filter| saved_filter.name == name)
+        else {
+            return;
+        };
+        for chip in self.chips() {
+            let active = chip
+                .label()
+                .is_some_and(|label| saved_filter.chips.iter().any(|saved| *saved == label));
+            chip.set_active(active);
+        }
+        self.imp().search_filter.set_text(&saved_filter.query);
+        if !saved_filter.query.is_empty() {
+            self.imp().search_bar.set_search_mode(true);
+        }
+    }
+
+    fn delete_filter(&self, name: &str) {
+        if let Err(error) = SavedFilter::delete(self.settings(), name) {
+            self.imp().overlay.add_toast(Toast::new(&format!(
+                "Failed to delete filter {name}: {error}"
+            )));
+        }
+    }
+
+    fn setup_columns_dialog(&self) {
+        self.imp()
+            .column_view
+            .columns()
+            .connect_items_changed(clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, _, _, _| window.update_columns_list()
+            ));
+        self.update_columns_list();
+
+        self.imp().column_property_entry.connect_apply(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |entry| {
+                let property = entry.text().trim().to_string();
+                if property.is_empty() || !property.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    entry.add_css_class("error");
+                    return;
+                }
+                entry.remove_css_class("error");
+                entry.set_text("");
+                window.add_property_column(property);
+            }
+        ));
+    }
+
+    /// Lists the columns with a switch for their visibility and buttons to move and remove them.
+    fn update_columns_list(&self) {
+        let columns_list = self.imp().columns_list.get();
+        while let Some(row) = columns_list.row_at_index(0) {
+            columns_list.remove(&row);
+        }
+        let column_view = self.imp().column_view.get();
+        let columns = column_view.columns();
+        let columns_len = columns.n_items();
+        for (position, column) in columns.iter::<ColumnViewColumn>().flatten().enumerate() {
+            let id = column.id().unwrap_or_default().to_string();
+            let row = ActionRow::new();
+            row.set_title(&id);
+            if table::is_property_column(&id) {
+                row.set_subtitle("Unit property");
+            }
+
+            let move_button = |icon_name: &str, tooltip: &str, new_position: u32| {
+                let button = Button::from_icon_name(icon_name);
+                button.set_tooltip_text(Some(tooltip));
+                button.set_valign(Align::Center);
+                button.add_css_class("flat");
+                button.connect_clicked(clone!(
+                    #[weak]
+                    column_view,
+                    #[weak]
+                    column,
+                    move |_| column_view.insert_column(new_position, &column)
+                ));
+                button
+            };
+            let position = position as u32;
+            let up_button = move_button("go-up-symbolic", "Move Up", position.saturating_sub(1));
+            up_button.set_sensitive(position > 0);
+            row.add_suffix(&up_button);
+            let down_button = move_button("go-down-symbolic", "Move Down", position + 1);
+            down_button.set_sensitive(position + 1 < columns_len);
+            row.add_suffix(&down_button);
+
+            if table::is_property_column(&id) {
+                let remove_button = Button::from_icon_name("user-trash-symbolic");
+                remove_button.set_tooltip_text(Some("Remove"));
+                remove_button.set_valign(Align::Center);
+                remove_button.add_css_class("flat");
+                remove_button.connect_clicked(clone!(
+                    #[weak]
+                    column_view,
+                    move |_| table::remove_property_column(&column_view, &id)
+                ));
+                row.add_suffix(&remove_button);
+            }
+
+            let visible_switch = Switch::new();
+            visible_switch.set_valign(Align::Center);
+            column
+                .bind_property("visible", &visible_switch, "active")
+                .sync_create()
+                .bidirectional()
+                .build();
+            row.add_suffix(&visible_switch);
+            row.set_activatable_widget(Some(&visible_switch));
+            columns_list.append(&row);
+        }
+    }
+
+    /// Loads the property for all units and adds a column for it.
+    fn add_property_column(&self, property: String) {
+        let column_property = property.clone();
+        self.load_unit_property(property, move |window| {
+            table::add_property_column(
+                &window.imp().column_view,
+                &column_property,
+                window.settings(),
+            );
+        });
+    }
+
+    /// The `systemctl show` properties loaded along with the units, for the columns and the grouping.
+    fn unit_properties(&self) -> Vec<String> {
+        let mut properties = table::property_columns(self.settings());
+        if self.grouping() == Grouping::Slice {
+            properties.push(SLICE_PROPERTY.to_string());
+        }
+        properties
+    }
+
+    /// Loads the property for all loaded units in the background and calls `on_loaded` once it is there.
+    fn load_unit_property(&self, property: String, on_loaded: impl FnOnce(&Self) + 'static) {
+        let model = self.imp().list_store.clone().into_inner().unwrap();
+        let units = model.iter::<UnitObject>().flatten().collect::<Vec<_>>();
+        let unit_names = units.iter().map(UnitObject::unit_name).collect::<Vec<_>>();
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let properties = vec![property.clone()];
+                let values =
+                    gio::spawn_blocking(move || systemd::show_properties(&unit_names, &properties))
+                        .await
+                        .expect("The lookup needs to finish.");
+                match values {
+                    Ok(values) => {
+                        for (unit_object, values) in units.iter().zip(values) {
+                            unit_object.set_unit_properties(values);
+                        }
+                        on_loaded(&window);
+                    }
+                    Err(error) => window.imp().overlay.add_toast(Toast::new(&format!(
+                        "Failed to fetch property {property}: {error}"
+                    ))),
+                }
+            }
+        ));
+    }
+
+    /// Explains the row styles and icons of the unit table in a popover.
+    fn setup_legend(&self) {
+        let legend = table::build_legend();
+        legend.set_margin_start(6);
+        legend.set_margin_end(6);
+        legend.set_margin_top(6);
+        legend.set_margin_bottom(6);
+        let popover = Popover::builder().child(&legend).build();
+        self.imp().legend_button.set_popover(Some(&popover));
+    }
+
+    /// Shows the selected unit once the details pane is opened, e.g. with its header bar button.
+    fn setup_detail_pane(&self) {
+        self.imp()
+            .detail_split_view
+            .connect_show_sidebar_notify(clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |split_view| {
+                    if !split_view.shows_sidebar() {
+                        return;
+                    }
+                    if let [unit_object] = window.selected_units().as_slice() {
+                        let unit_name = unit_object.unit_name();
+                        let unit_detail = &window.imp().unit_detail;
+                        if !unit_detail.shows_unit(&unit_name) {
+                            unit_detail.show_unit(&unit_name, None);
+                        }
+                    }
+                }
+            ));
+    }
+
+    /// Shows the filter chips only with the unit table and loads the control groups whenever their page is shown.
+    fn setup_pages(&self) {
+        self.imp()
+            .page_stack
+            .connect_visible_child_name_notify(clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |page_stack| {
+                    let page = page_stack.visible_child_name().unwrap_or_default();
+                    let imp = window.imp();
+                    imp.chip_bar.set_visible(page == "units");
+                    if page == "cgroups" {
+                        imp.cgroup_browser.reload();
+                    }
+                }
+            ));
+    }
+
+    fn setup_history(&self) {
+        let history_store = self.imp().history_store.clone().into_inner().unwrap();
+        self.imp()
+            .history_list
+            .bind_model(Some(&history_store), |item| {
+                let entry = item
+                    .downcast_ref::<HistoryEntry>()
+                    .expect("The object needs to be of type `HistoryEntry`.");
+                history::build_row(entry).upcast()
+            });
+    }
+
+    fn start_await_update(model: ListStore, overlay: ToastOverlay, properties: Vec<String>) {
+        let (units_receiver, toast_text_receiver) = Self::start_update(properties);
         Self::await_update(overlay, units_receiver, toast_text_receiver, model);
     }
 
@@ -82,12 +970,13 @@ impl Window {
         glib::spawn_future_local(Self::await_units_toast(toast_text_receiver, overlay_clone));
     }
 
-    fn start_update() -> (Receiver<Vec<UnitObject>>, Receiver<String>) {
+    /// Loads the units along with the `systemctl show` properties shown as columns.
+    fn start_update(properties: Vec<String>) -> (Receiver<Vec<UnitObject>>, Receiver<String>) {
         // Create a channel that can hold at most 1 message at a time
         let (units_sender, units_receiver) = async_channel::bounded(1);
         let (toast_text_sender, toast_text_receiver) = async_channel::bounded(1);
 
-        gio::spawn_blocking(move || Self::load_units(units_sender, toast_text_sender));
+        gio::spawn_blocking(move || Self::load_units(&properties, units_sender, toast_text_sender));
         (units_receiver, toast_text_receiver)
     }
 
@@ -113,43 +1002,77 @@ impl Window {
         )
     }
 
-    fn search_filter(filter_input_lower_case: &Rc<RefCell<String>>, obj: &Object) -> bool {
+    fn search_filter(unit_filter: &Rc<RefCell<UnitFilter>>, obj: &Object) -> bool {
         // Get `UnitObject` from `glib::Object`
         let unit_object = obj
             .downcast_ref::<UnitObject>()
             .expect("The object needs to be of type `UnitObject`.");
 
-        // Check if unit_object's unit_name contains the filter value
-        let input = &filter_input_lower_case.borrow().to_string();
-        if unit_object.unit_name().to_lowercase().contains(input) {
-            true
-        } else {
-            unit_object.description().to_lowercase().contains(input)
-        }
+        unit_filter.borrow().matches(unit_object)
+    }
+
+    fn relevance_sort(
+        unit_filter: &Rc<RefCell<UnitFilter>>,
+        one: &Object,
+        two: &Object,
+    ) -> gtk::Ordering {
+        let unit_filter = unit_filter.borrow();
+        let score = |obj: &Object| {
+            let unit_object = obj
+                .downcast_ref::<UnitObject>()
+                .expect("The object needs to be of type `UnitObject`.");
+            unit_filter.query.score(unit_object)
+        };
+        // descending, higher scores first
+        score(two).cmp(&score(one)).into()
     }
 
-    fn load_units(units_sender: Sender<Vec<UnitObject>>, toast_text_sender: Sender<String>) {
+    fn load_units(
+        properties: &[String],
+        units_sender: Sender<Vec<UnitObject>>,
+        toast_text_sender: Sender<String>,
+    ) {
         let start = Instant::now();
-        let items = systemd::units();
+        let items = match systemd::units() {
+            Ok(items) => items,
+            Err(error) => {
+                toast_text_sender
+                    .send_blocking(format!("Could not load the units: {error}"))
+                    .expect("The channel needs to be open.");
+                return;
+            }
+        };
         let items_len = items.len();
+        let properties_result = Self::load_unit_properties(&items, properties);
         units_sender
             .clone()
             .send_blocking(items)
             .expect("The channel needs to be open.");
         let duration = start.elapsed().as_millis();
-        let info_text = format!("Fetched {} units in {}ms", items_len, duration);
+        let info_text = match properties_result {
+            Ok(()) => format!("Fetched {} units in {}ms", items_len, duration),
+            Err(error) => format!(
+                "Fetched {} units in {}ms, failed to fetch column properties: {error}",
+                items_len, duration
+            ),
+        };
         toast_text_sender
             .clone()
             .send_blocking(info_text)
             .expect("The channel needs to be open.");
     }
 
-    fn connect_selection_changed(&self, single_selection: &SingleSelection) {
+    fn load_unit_properties(units: &[UnitObject], properties: &[String]) -> std::io::Result<()> {
+        let unit_names = units.iter().map(UnitObject::unit_name).collect::<Vec<_>>();
+        let values = systemd::show_properties(&unit_names, properties)?;
+        for (unit_object, values) in units.iter().zip(values) {
+            unit_object.set_unit_properties(values);
+        }
+        Ok(())
+    }
+
+    fn connect_selection_changed(&self, multi_selection: &MultiSelection) {
         let bottom_bar_clone = self.imp().bottom_bar.clone();
-        let view_unit_button_clone = self.imp().view_unit_button.clone();
-        let dialog_clone = self.imp().dialog.clone();
-        let text_view_clone = self.imp().text_view.clone();
-        let self_clone = self.clone();
         let search_bar_clone = self.imp().search_bar.clone();
 
         let start_button_clone = self.imp().start_button.clone();
@@ -157,51 +1080,33 @@ impl Window {
         let restart_button_clone = self.imp().restart_button.clone();
         let enable_button_clone = self.imp().enable_button.clone();
         let disable_button_clone = self.imp().disable_button.clone();
+        let view_unit_button_clone = self.imp().view_unit_button.clone();
+        let detail_split_view_clone = self.imp().detail_split_view.clone();
+        let unit_detail_clone = self.imp().unit_detail.clone();
 
-        let list_store = self.imp().list_store.clone().into_inner().unwrap();
-        let overlay = self.imp().overlay.clone();
-
-        single_selection.connect_selection_changed(move |selection, _, _| {
+        multi_selection.connect_selection_changed(move |selection, _, _| {
+            let unit_objects = Self::selected_units_of(selection);
+            bottom_bar_clone.set_revealed(!unit_objects.is_empty());
+            if unit_objects.is_empty() {
+                return;
+            }
             search_bar_clone.set_search_mode(false);
-            bottom_bar_clone.set_revealed(true);
-            let unit_object = selection
-                .selected_item()
-                .unwrap()
-                .downcast::<UnitObject>()
-                .unwrap();
-            view_unit_button_clone.connect_clicked(clone!(
-                #[weak]
-                dialog_clone,
-                #[weak]
-                self_clone,
-                move |_| {
-                    dialog_clone.present(Some(&self_clone));
-                }
-            ));
 
-            let unit_file_content = systemd::cat(unit_object.clone());
-            if let Ok(content) = unit_file_content {
-                // open new text buffer, otherwise the content will be concatenated
-                text_view_clone.set_buffer(Some(&TextBuffer::default()));
-                text_view_clone
-                    .buffer()
-                    .write_str(content.as_str())
-                    .expect("Couldn't write to buffer.");
-                view_unit_button_clone.set_sensitive(true);
-                text_view_clone.set_vexpand(true);
-                text_view_clone.set_hexpand(true);
-
-                let file_path = content
-                    .lines()
-                    .next()
-                    .unwrap()
-                    .split_whitespace()
-                    .nth(1)
-                    .unwrap()
-                    .to_string(); // Clone as an owned String
-                dialog_clone.set_title(&file_path);
+            // the unit file can only be viewed for a single unit
+            if let [unit_object] = unit_objects.as_slice() {
+                let target = unit_object.unit_name().to_variant();
+                view_unit_button_clone.set_action_target_value(Some(&target));
+                view_unit_button_clone.set_visible(true);
+
+                // the details pane follows the selection while it is shown
+                let unit_name = unit_object.unit_name();
+                if detail_split_view_clone.shows_sidebar()
+                    && !unit_detail_clone.shows_unit(&unit_name)
+                {
+                    unit_detail_clone.show_unit(&unit_name, None);
+                }
             } else {
-                view_unit_button_clone.set_sensitive(false);
+                view_unit_button_clone.set_visible(false);
             }
 
             // Define a list of actions and their corresponding buttons
@@ -213,19 +1118,13 @@ impl Window {
                 (&SystemCtrlAction::Disable, &disable_button_clone),
             ];
 
-            // Get the available actions once
-            let available_actions = SystemCtrlAction::available_actions(&unit_object);
+            // Get the actions available for every selected unit once
+            let available_actions = SystemCtrlAction::common_actions(&unit_objects);
 
             // Iterate over each (action, button) pair
             for (action, button) in actions_buttons {
                 if available_actions.contains(action) {
-                    Self::enable_button(
-                        action,
-                        button,
-                        unit_object.clone(),
-                        list_store.clone(),
-                        overlay.clone(),
-                    );
+                    Self::enable_button(button);
                 } else {
                     Self::disable_button(button);
                 }
@@ -233,73 +1132,730 @@ impl Window {
         });
     }
 
+    /// Returns the currently selected units.
+    fn selected_units(&self) -> Vec<UnitObject> {
+        self.imp()
+            .column_view
+            .model()
+            .and_downcast::<MultiSelection>()
+            .map(|selection| Self::selected_units_of(&selection))
+            .unwrap_or_default()
+    }
+
+    fn selected_units_of(selection: &MultiSelection) -> Vec<UnitObject> {
+        let bitset = selection.selection();
+        (0..bitset.size() as u32)
+            .filter_map(|index| selection.item(bitset.nth(index)))
+            .filter_map(|item| table::unit_object_of(&item))
+            .collect()
+    }
+
+    /// Looks up the unit with the given name in the list of loaded units.
+    fn find_unit(&self, unit_name: &str) -> Option<UnitObject> {
+        let list_store = self.imp().list_store.clone().into_inner()?;
+        let unit_object = list_store
+            .iter::<UnitObject>()
+            .flatten()
+            .find(|unit_object| unit_object.unit_name() == unit_name);
+        unit_object
+    }
+
+    /// Shows the unit details in the side pane, on the given view if any, which are loaded in the background.
+    fn view_unit(&self, unit_name: &str, view: Option<&str>) {
+        let imp = self.imp();
+        imp.unit_detail.show_unit(unit_name, view);
+        imp.detail_split_view.set_show_sidebar(true);
+    }
+
+    /// Selects the unit in the table and shows its details on the given view, if any.
+    ///
+    /// If the units are still loading, the unit is selected once they are there.
+    pub fn open_unit(&self, unit_name: &str, view: Option<&str>) {
+        if !self.select_unit(unit_name) {
+            self.imp()
+                .pending_selection
+                .replace(Some(unit_name.to_string()));
+        }
+        if view.is_some() {
+            self.view_unit(unit_name, view);
+        }
+    }
+
+    /// Selects only the given unit and scrolls to it, returns `false` if it is not shown in the table.
+    fn select_unit(&self, unit_name: &str) -> bool {
+        let column_view = self.imp().column_view.get();
+        let Some(selection) = column_view.model().and_downcast::<MultiSelection>() else {
+            return false;
+        };
+        // the unit is only part of the tree if its group is expanded
+        let group_key = self
+            .find_unit(unit_name)
+            .and_then(|unit_object| self.grouping().key(&unit_object));
+        if let (Some(tree), Some(group_key)) = (self.group_tree(), group_key) {
+            let group_row = (0..tree.n_items())
+                .filter_map(|position| tree.row(position))
+                .find(|row| {
+                    row.item()
+                        .and_downcast::<GroupObject>()
+                        .is_some_and(|group| group.name() == group_key)
+                });
+            if let Some(group_row) = group_row {
+                group_row.set_expanded(true);
+            }
+        }
+        let position = selection.iter::<Object>().flatten().position(|item| {
+            table::unit_object_of(&item)
+                .is_some_and(|unit_object| unit_object.unit_name() == unit_name)
+        });
+        let Some(position) = position else {
+            return false;
+        };
+        selection.select_item(position as u32, true);
+        self.scroll_to_position(position as u32);
+        true
+    }
+
+    /// Scrolls the table to the row at `position`.
+    ///
+    /// Built with the `gtk_4_12` feature and running on GTK 4.12 or later, this uses `ColumnView::scroll_to`.
+    /// Otherwise the row is centered by estimating its offset from the scrollable height, as all rows of the table
+    /// are equally high, which may be off by a few pixels for the header.
+    fn scroll_to_position(&self, position: u32) {
+        let column_view = self.imp().column_view.get();
+        #[cfg(feature = "gtk_4_12")]
+        if gtk::check_version(4, 12, 0).is_none() {
+            column_view.scroll_to(position, None, gtk::ListScrollFlags::NONE, None);
+            return;
+        }
+        let n_items = column_view.model().map_or(0, |model| model.n_items());
+        let Some(adjustment) = column_view.vadjustment().filter(|_| n_items > 0) else {
+            return;
+        };
+        let row_height = adjustment.upper() / f64::from(n_items);
+        // the adjustment clamps the value to its bounds
+        adjustment.set_value(
+            row_height * f64::from(position) - (adjustment.page_size() - row_height) / 2.0,
+        );
+    }
+
+    /// Like `select_unit`, but tells the user if the unit is not shown, e.g. because it is filtered out.
+    fn select_unit_or_notify(&self, unit_name: &str) -> bool {
+        let selected = self.select_unit(unit_name);
+        if !selected {
+            self.imp().overlay.add_toast(Toast::new(&format!(
+                "{unit_name} is not shown in the unit table"
+            )));
+        }
+        selected
+    }
+
+    /// Presents the audit log, newest entries first.
+    fn show_audit_log(&self) {
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let log = gio::spawn_blocking(audit::read)
+                    .await
+                    .expect("Reading the audit log needs to finish.");
+                let imp = window.imp();
+                match log {
+                    Ok(log) => {
+                        while let Some(row) = imp.audit_list.row_at_index(0) {
+                            imp.audit_list.remove(&row);
+                        }
+                        for entry in log.entries.iter().rev() {
+                            imp.audit_list.append(&audit_row(entry));
+                        }
+                        imp.audit_banner.set_title(&match log.skipped_lines {
+                            1 => String::from("1 line of the log could not be read"),
+                            lines => format!("{lines} lines of the log could not be read"),
+                        });
+                        imp.audit_banner.set_revealed(log.skipped_lines > 0);
+                        imp.audit_dialog.present(Some(&window));
+                    }
+                    Err(error) => imp.overlay.add_toast(Toast::new(&format!(
+                        "Could not read the audit log: {error}"
+                    ))),
+                }
+            }
+        ));
+    }
+
+    /// Asks for a destination and exports the audit log as JSON Lines.
+    fn export_audit_log(&self) {
+        let file_dialog = FileDialog::builder()
+            .title("Export Audit Log")
+            .initial_name("audit.jsonl")
+            .build();
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let Some(path) = file_dialog
+                    .save_future(Some(&window))
+                    .await
+                    .ok()
+                    .and_then(|file| file.path())
+                else {
+                    return;
+                };
+                let destination = path.clone();
+                let exported = gio::spawn_blocking(move || audit::export(&destination))
+                    .await
+                    .expect("The export needs to finish.");
+                let toast_text = match exported {
+                    Ok(()) => format!("Exported audit log to {}", path.display()),
+                    Err(error) => format!("Could not export the audit log: {error}"),
+                };
+                window.imp().overlay.add_toast(Toast::new(&toast_text));
+            }
+        ));
+    }
+
+    /// Asks for a destination and exports the table as shown, the format is picked by file extension.
+    fn export_units(&self) {
+        let (headers, rows) = table::visible_rows(&self.imp().column_view);
+        let filters = ListStore::new::<FileFilter>();
+        for format in ExportFormat::ALL {
+            let filter = FileFilter::new();
+            filter.set_name(Some(format.name()));
+            filter.add_suffix(format.extension());
+            filters.append(&filter);
+        }
+        let file_dialog = FileDialog::builder()
+            .title("Export Units")
+            .initial_name("units.csv")
+            .filters(&filters)
+            .build();
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let Some(path) = file_dialog
+                    .save_future(Some(&window))
+                    .await
+                    .ok()
+                    .and_then(|file| file.path())
+                else {
+                    return;
+                };
+                // the dialog keeps the filter chosen by the user as default filter once it is closed
+                let selected_format = file_dialog
+                    .default_filter()
+                    .and_then(|filter| filter.name())
+                    .and_then(|name| ExportFormat::from_name(&name))
+                    .unwrap_or(ExportFormat::Csv);
+                let (format, path) = ExportFormat::resolve(path, selected_format);
+                let content = format.format(&headers, &rows);
+                let destination = path.clone();
+                let written = gio::spawn_blocking(move || fs::write(destination, content))
+                    .await
+                    .expect("The export needs to finish.");
+                let toast_text = match written {
+                    Ok(()) => format!("Exported {} units to {}", rows.len(), path.display()),
+                    Err(error) => format!("Could not export units: {error}"),
+                };
+                window.imp().overlay.add_toast(Toast::new(&toast_text));
+            }
+        ));
+    }
+
     fn build_search_filter(
         &self,
         filter: CustomFilter,
-        filter_value_for_search: Rc<RefCell<String>>,
+        relevance_sorter: CustomSorter,
+        unit_filter: Rc<RefCell<UnitFilter>>,
     ) {
         let search_filter = self.imp().search_filter.get();
+        let search_error = self.imp().search_error.get();
+        // the entry is not the direct child of the search bar, so it has to be connected explicitly
+        self.imp().search_bar.connect_entry(&search_filter);
 
         search_filter.connect_search_changed(move |input| {
-            // Update the filter_value inside RefCell
-            *filter_value_for_search.borrow_mut() = input.text().to_lowercase();
+            // Keep filtering by the last valid query while the input has errors
+            match Query::parse(&input.text()) {
+                Ok(query) => {
+                    input.remove_css_class("error");
+                    search_error.set_visible(false);
+                    unit_filter.borrow_mut().query = query;
+                }
+                Err(message) => {
+                    input.add_css_class("error");
+                    search_error.set_label(&message);
+                    search_error.set_tooltip_text(Some(&message));
+                    search_error.set_visible(true);
+                    return;
+                }
+            }
 
-            // Notify that the filter has changed
+            // Notify that the filter and the relevance have changed
             filter.changed(FilterChange::Different);
+            relevance_sorter.changed(SorterChange::Different);
         });
     }
 
+    fn build_filter_chips(&self, filter: CustomFilter, unit_filter: Rc<RefCell<UnitFilter>>) {
+        let chip_box = self.imp().chip_box.get();
+
+        for unit_type in UNIT_TYPES {
+            let chip = Self::build_filter_chip(unit_type, &filter, &unit_filter, |unit_filter| {
+                &mut unit_filter.types
+            });
+            chip_box.append(&chip);
+        }
+
+        chip_box.append(&Separator::new(Orientation::Vertical));
+
+        for state in STATES {
+            let chip = Self::build_filter_chip(state, &filter, &unit_filter, |unit_filter| {
+                &mut unit_filter.states
+            });
+            chip_box.append(&chip);
+        }
+    }
+
+    fn build_filter_chip(
+        label: &'static str,
+        filter: &CustomFilter,
+        unit_filter: &Rc<RefCell<UnitFilter>>,
+        chips_of: fn(&mut UnitFilter) -> &mut HashSet<String>,
+    ) -> ToggleButton {
+        let chip = ToggleButton::with_label(label);
+        chip.add_css_class("chip");
+        chip.connect_toggled(clone!(
+            #[strong]
+            filter,
+            #[strong]
+            unit_filter,
+            move |chip| {
+                let mut unit_filter = unit_filter.borrow_mut();
+                let chips = chips_of(&mut unit_filter);
+                if chip.is_active() {
+                    chips.insert(label.to_string());
+                } else {
+                    chips.remove(label);
+                }
+                drop(unit_filter);
+
+                filter.changed(FilterChange::Different);
+            }
+        ));
+        chip
+    }
+
     fn setup_actions(&self) {
         let search_bar_action = ActionEntry::builder("search_bar_show")
             .activate(|window: &Self, _, _| window.imp().search_bar.set_search_mode(true))
             .build();
         let view_unit_action = ActionEntry::builder("view_unit_action")
             .activate(|window: &Self, _, _| {
-                let button = &window.imp().view_unit_button;
-                if button.get_sensitive() {
-                    button.emit_clicked()
+                if let [unit_object] = window.selected_units().as_slice() {
+                    window.view_unit(&unit_object.unit_name(), Some("file"));
                 }
             })
             .build();
+        let history_action = ActionEntry::builder("history_show")
+            .activate(|window: &Self, _, _| {
+                let split_view = &window.imp().history_split_view;
+                split_view.set_show_sidebar(!split_view.shows_sidebar());
+            })
+            .build();
+        let audit_log_action = ActionEntry::builder("audit_log_show")
+            .activate(|window: &Self, _, _| window.show_audit_log())
+            .build();
+        let audit_log_export_action = ActionEntry::builder("audit_log_export")
+            .activate(|window: &Self, _, _| window.export_audit_log())
+            .build();
+        let export_action = ActionEntry::builder("export_units")
+            .activate(|window: &Self, _, _| window.export_units())
+            .build();
+        // the grouping is a stateful action bound to the setting, e.g. `win.grouping('type')`
+        self.add_action(&self.settings().create_action(GROUPING_KEY));
+        self.add_action(&self.settings().create_action(RUN_IN_BACKGROUND_KEY));
+        let columns_action = ActionEntry::builder("columns_show")
+            .activate(|window: &Self, _, _| window.imp().columns_dialog.present(Some(window)))
+            .build();
+        let column_hide_action = ActionEntry::builder("column-hide")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                let id = parameter
+                    .and_then(String::from_variant)
+                    .expect("The action parameter needs to be a column id.");
+                if let Some(column) = table::find_column(&window.imp().column_view, &id) {
+                    column.set_visible(false);
+                }
+            })
+            .build();
+        let unit_pin_action = ActionEntry::builder("unit-pin")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                window.toggle_favorite(&Self::unit_name_parameter(parameter));
+            })
+            .build();
+        let unit_watch_action = ActionEntry::builder("unit-watch")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                window.toggle_watched(&Self::unit_name_parameter(parameter));
+            })
+            .build();
+        let filter_save_action = ActionEntry::builder("filter-save")
+            .activate(|window: &Self, _, _| window.save_filter())
+            .build();
+        let filter_apply_action = ActionEntry::builder("filter-apply")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                window.apply_filter(&Self::filter_name_parameter(parameter));
+            })
+            .build();
+        let filter_delete_action = ActionEntry::builder("filter-delete")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                window.delete_filter(&Self::filter_name_parameter(parameter));
+            })
+            .build();
+        let unit_select_action = ActionEntry::builder("unit-select")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                window.select_unit_or_notify(&Self::unit_name_parameter(parameter));
+            })
+            .build();
+        // like `unit-select`, but also switches to the unit table
+        let unit_reveal_action = ActionEntry::builder("unit-reveal")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                if window.select_unit_or_notify(&Self::unit_name_parameter(parameter)) {
+                    window.imp().page_stack.set_visible_child_name("units");
+                }
+            })
+            .build();
+        let unit_view_action = ActionEntry::builder("unit-view")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(|window: &Self, _, parameter| {
+                window.view_unit(&Self::unit_name_parameter(parameter), None);
+            })
+            .build();
+
+        // one action per systemctl verb, taking the unit name as target, e.g. `win.unit-start('foo.service')`
+        let unit_actions = SystemCtrlAction::iter().map(|action| {
+            ActionEntry::builder(&format!("unit-{}", action.verb()))
+                .parameter_type(Some(&String::static_variant_type()))
+                .activate(move |window: &Self, _, parameter| {
+                    window.confirm_action(action, vec![Self::unit_name_parameter(parameter)]);
+                })
+                .build()
+        });
+        // and one acting on all selected units, e.g. `win.selection-start`
+        let selection_actions = SystemCtrlAction::iter().map(|action| {
+            ActionEntry::builder(&format!("selection-{}", action.verb()))
+                .activate(move |window: &Self, _, _| {
+                    let unit_names = window
+                        .selected_units()
+                        .iter()
+                        .map(UnitObject::unit_name)
+                        .collect();
+                    window.confirm_action(action, unit_names);
+                })
+                .build()
+        });
 
-        self.add_action_entries([search_bar_action, view_unit_action]);
+        self.add_action_entries([
+            search_bar_action,
+            view_unit_action,
+            history_action,
+            audit_log_action,
+            audit_log_export_action,
+            export_action,
+            unit_view_action,
+            unit_select_action,
+            unit_reveal_action,
+            unit_pin_action,
+            unit_watch_action,
+            columns_action,
+            column_hide_action,
+            filter_save_action,
+            filter_apply_action,
+            filter_delete_action,
+        ]);
+        self.add_action_entries(unit_actions);
+        self.add_action_entries(selection_actions);
     }
 
-    fn enable_button(
-        action: &SystemCtrlAction,
-        button: &Button,
-        unit: UnitObject,
-        model: ListStore,
-        overlay: ToastOverlay,
-    ) {
-        match action {
-            SystemCtrlAction::Start => button.connect_clicked(move |_| {
-                systemd::start(unit.clone());
-                Self::start_await_update(model.clone(), overlay.clone());
-            }),
-            SystemCtrlAction::Stop => button.connect_clicked(move |_| {
-                systemd::stop(unit.clone());
-                Self::start_await_update(model.clone(), overlay.clone());
-            }),
-            SystemCtrlAction::Restart => button.connect_clicked(move |_| {
-                systemd::restart(unit.clone());
-                Self::start_await_update(model.clone(), overlay.clone());
-            }),
-
-            SystemCtrlAction::Enable => button.connect_clicked(move |_| {
-                systemd::enable(unit.clone());
-                Self::start_await_update(model.clone(), overlay.clone());
-            }),
-            SystemCtrlAction::Disable => button.connect_clicked(move |_| {
-                systemd::disable(unit.clone());
-                Self::start_await_update(model.clone(), overlay.clone());
-            }),
-        };
+    fn unit_name_parameter(parameter: Option<&glib::Variant>) -> String {
+        parameter
+            .and_then(String::from_variant)
+            .expect("The action parameter needs to be a unit name.")
+    }
+
+    fn filter_name_parameter(parameter: Option<&glib::Variant>) -> String {
+        parameter
+            .and_then(String::from_variant)
+            .expect("The action parameter needs to be a filter name.")
+    }
 
+    fn enable_button(button: &Button) {
         button.set_visible(true);
     }
 
+    /// Asks for confirmation before running destructive actions, listing the active units depending on the units.
+    fn confirm_action(&self, action: SystemCtrlAction, unit_names: Vec<String>) {
+        let confirm_skip_units = self.settings().strv(CONFIRM_SKIP_UNITS_KEY);
+        let skip_confirmation = unit_names.iter().all(|unit_name| {
+            confirm_skip_units
+                .iter()
+                .any(|skipped| skipped == unit_name)
+        });
+        if !action.is_destructive() || skip_confirmation {
+            self.run_action(action, unit_names);
+            return;
+        }
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let dependency_unit_names = unit_names.clone();
+                let dependents = gio::spawn_blocking(move || {
+                    systemd::reverse_dependencies(&dependency_unit_names)
+                })
+                .await
+                .expect("The lookup needs to finish.");
+                let active_dependents = dependents.map(|dependents| {
+                    dependents
+                        .into_iter()
+                        .filter(|unit_name| {
+                            window
+                                .find_unit(unit_name)
+                                .is_some_and(|unit_object| unit_object.state() == "active")
+                        })
+                        .collect::<Vec<_>>()
+                });
+
+                let heading = match unit_names.as_slice() {
+                    [unit_name] => format!("{action} {unit_name}?"),
+                    _ => format!("{action} {} units?", unit_names.len()),
+                };
+                let body = match active_dependents {
+                    Ok(active_dependents) if active_dependents.is_empty() => {
+                        String::from("No active units depend on this.")
+                    }
+                    Ok(active_dependents) => format!(
+                        "These active units depend on this and may be affected:\n{}",
+                        active_dependents.join("\n")
+                    ),
+                    Err(error) => format!(
+                        "The impact could not be determined, units depending on this may be affected.\n{error}"
+                    ),
+                };
+                let dialog = AlertDialog::new(Some(&heading), Some(&body));
+                dialog.add_responses(&[("cancel", "Cancel"), ("confirm", &action.to_string())]);
+                dialog.set_response_appearance("confirm", ResponseAppearance::Destructive);
+                dialog.set_default_response(Some("cancel"));
+                dialog.set_close_response("cancel");
+
+                let command = Label::new(Some(&format!(
+                    "systemctl {} {}",
+                    action.verb(),
+                    unit_names.join(" ")
+                )));
+                command.set_selectable(true);
+                command.set_wrap(true);
+                command.add_css_class("monospace");
+                let dont_ask = CheckButton::with_label(if unit_names.len() == 1 {
+                    "Don't ask again for this unit"
+                } else {
+                    "Don't ask again for these units"
+                });
+                let extra_child = gtk::Box::new(Orientation::Vertical, 12);
+                extra_child.append(&command);
+                extra_child.append(&dont_ask);
+                dialog.set_extra_child(Some(&extra_child));
+
+                if dialog.choose_future(&window).await == "confirm" {
+                    if dont_ask.is_active() {
+                        window.skip_confirmation(&unit_names);
+                    }
+                    window.run_action(action, unit_names);
+                }
+            }
+        ));
+    }
+
+    /// Remembers to run destructive actions on the units without asking for confirmation.
+    fn skip_confirmation(&self, unit_names: &[String]) {
+        let mut skipped = self
+            .settings()
+            .strv(CONFIRM_SKIP_UNITS_KEY)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        for unit_name in unit_names {
+            if !skipped.contains(unit_name) {
+                skipped.push(unit_name.clone());
+            }
+        }
+        if let Err(error) = self.settings().set_strv(CONFIRM_SKIP_UNITS_KEY, skipped) {
+            self.imp()
+                .overlay
+                .add_toast(Toast::new(&format!("Failed to save the choice: {error}")));
+        }
+    }
+
+    /// Runs the action for all units off the main thread and toasts the job results once they have finished.
+    fn run_action(&self, action: SystemCtrlAction, unit_names: Vec<String>) {
+        if unit_names.is_empty() {
+            return;
+        }
+        let model = self.imp().list_store.clone().into_inner().unwrap();
+        let overlay = self.imp().overlay.get();
+        self.set_busy(&unit_names, true);
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let job_unit_names = unit_names.clone();
+                // the states are read right before the jobs run, the table may be outdated
+                let (previous_states, audited) = gio::spawn_blocking(move || {
+                    let previous_states = action.states(&job_unit_names);
+                    (previous_states, action.run(&job_unit_names))
+                })
+                .await
+                .expect("The jobs need to finish.");
+                window.set_busy(&unit_names, false);
+                // the next sample is compared to the state the jobs left the units in, not the one before
+                window
+                    .imp()
+                    .watched_statuses
+                    .borrow_mut()
+                    .retain(|unit_name, _| !unit_names.contains(unit_name));
+                let results = audited.result;
+                window.record_history(action, &unit_names, &previous_states, &results);
+                window.toast_results(action, &unit_names, &results);
+                if let Some(error) = audited.audit_error {
+                    toast(&window, &format!("Could not write the audit log: {error}"));
+                }
+                window.imp().unit_detail.reload_unit(&unit_names);
+                Self::start_await_update(model, overlay, window.unit_properties());
+            }
+        ));
+    }
+
+    fn record_history(
+        &self,
+        action: SystemCtrlAction,
+        unit_names: &[String],
+        previous_states: &[String],
+        results: &[JobResult],
+    ) {
+        let history_store = self.imp().history_store.clone().into_inner().unwrap();
+        for ((unit_name, previous_state), result) in
+            unit_names.iter().zip(previous_states).zip(results)
+        {
+            let entry = HistoryEntry::new(unit_name, action, previous_state, *result);
+            history_store.insert(0, &entry);
+        }
+    }
+
+    /// Toasts the job result, or a summary with per-unit details if the action ran for several units.
+    fn toast_results(
+        &self,
+        action: SystemCtrlAction,
+        unit_names: &[String],
+        results: &[JobResult],
+    ) {
+        let overlay = self.imp().overlay.get();
+        if let ([unit_name], [result]) = (unit_names, results) {
+            overlay.add_toast(Toast::new(&format!("{action} {unit_name}: {result}")));
+            return;
+        }
+
+        let mut counts: Vec<(JobResult, usize)> = vec![];
+        for result in results {
+            match counts.iter_mut().find(|(counted, _)| counted == result) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((*result, 1)),
+            }
+        }
+        let summary = counts
+            .iter()
+            .map(|(result, count)| format!("{count} {result}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let details = unit_names
+            .iter()
+            .zip(results)
+            .map(|(unit_name, result)| format!("{unit_name}: {result}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let toast = Toast::builder()
+            .title(format!("{action} {} units: {summary}", unit_names.len()))
+            .button_label("Details")
+            .build();
+        toast.connect_button_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| {
+                let dialog = AlertDialog::new(Some(&format!("{action} results")), Some(&details));
+                dialog.add_response("close", "Close");
+                dialog.present(Some(&window));
+            }
+        ));
+        overlay.add_toast(toast);
+    }
+
+    /// Marks the units as having a job of this app running, kept by name as reloading replaces the units.
+    fn set_busy(&self, unit_names: &[String], busy: bool) {
+        let imp = self.imp();
+        {
+            let mut busy_units = imp.busy_units.borrow_mut();
+            for unit_name in unit_names {
+                if busy {
+                    busy_units.insert(unit_name.clone());
+                } else {
+                    busy_units.remove(unit_name);
+                }
+            }
+        }
+        let Some(model) = imp.list_store.borrow().clone() else {
+            return;
+        };
+        // the spinner in the unit column is bound to `busy`
+        for unit_object in model.iter::<UnitObject>().flatten() {
+            if unit_names.contains(&unit_object.unit_name()) {
+                unit_object.set_busy(busy);
+            }
+        }
+    }
+
     fn disable_button(button: &Button) {
         button.set_visible(false);
     }
 }
+
+/// Toasts the message in the window containing the widget, for widgets without a toast overlay of their own.
+pub fn toast(widget: &impl IsA<gtk::Widget>, message: &str) {
+    if let Some(window) = widget.root().and_downcast::<Window>() {
+        window.imp().overlay.add_toast(Toast::new(message));
+    }
+}
+
+/// Builds the row showing an audit log entry in the viewer.
+fn audit_row(entry: &AuditEntry) -> ActionRow {
+    let row = ActionRow::builder()
+        .title(format!(
+            "{} {}: {}",
+            entry.action, entry.unit, entry.outcome
+        ))
+        .subtitle(format!("{} · {}", entry.timestamp, entry.user))
+        .build();
+    if !entry.error.is_empty() {
+        row.set_subtitle(&format!(
+            "{} · {} · {}",
+            entry.timestamp, entry.user, entry.error
+        ));
+    }
+    row.set_title_selectable(true);
+    row
+}